@@ -0,0 +1,84 @@
+#![cfg_attr(not(feature = "std"), no_std)]
+#![feature(min_specialization)]
+
+// build of the hotel contract with the storage of the hotel that writes rooms with the
+// layout of storage version 1, the e2e tests of the hotel contract upgrade a hotel to it
+// to seed old records and upgrade back to the current code before calling `migrate`
+#[openbrush::contract]
+mod legacy_hotel {
+    use ink::{prelude::string::String, storage::traits::StorageKey};
+    use logics::impls::room_book::{
+        migration::RoomV1,
+        types::{self, RoomId, DEFAULT_BILLING_PERIOD},
+    };
+    use openbrush::{
+        contracts::{
+            access_control, ownable, pausable,
+            psp34::{self, extensions::metadata},
+            reentrancy_guard,
+        },
+        traits::{Storage, ZERO_ADDRESS},
+    };
+
+    // same storage as `Hotel`
+    #[ink(storage)]
+    #[derive(Storage, Default)]
+    pub struct LegacyHotel {
+        #[storage_field]
+        ownable: ownable::Data,
+        #[storage_field]
+        access: access_control::Data,
+        #[storage_field]
+        pause: pausable::Data,
+        #[storage_field]
+        guard: reentrancy_guard::Data,
+        #[storage_field]
+        hotel_data: types::Data,
+        #[storage_field]
+        psp34: psp34::Data,
+        #[storage_field]
+        metadata: metadata::Data,
+    }
+
+    impl LegacyHotel {
+        #[ink(constructor)]
+        pub fn new() -> Self {
+            Self::default()
+        }
+
+        // stores `count` vacant rooms of the caller with the layout of version 1 and marks
+        // the storage as version 1
+        #[ink(message)]
+        pub fn seed_rooms(&mut self, count: RoomId, rent_per_month: Balance) {
+            let room_key = self.hotel_data.room.key();
+            for room_id in 0..count {
+                let room = RoomV1 {
+                    room_id,
+                    agreement_id: 0,
+                    room_name: String::from("legacy room"),
+                    room_address: String::from("legacy address"),
+                    rent_per_month,
+                    security_deposit: rent_per_month,
+                    time_stamp: self.env().block_timestamp(),
+                    vacant: true,
+                    landlord: self.env().caller(),
+                    current_tenant: ZERO_ADDRESS.into(),
+                    next_rent_due_date: 0,
+                    billing_period: DEFAULT_BILLING_PERIOD,
+                    late_fee_policy: Default::default(),
+                    lease_terms: Default::default(),
+                };
+                ink::env::set_contract_storage(&(&room_key, &room_id), &room);
+            }
+
+            self.hotel_data.room_id = count;
+            self.hotel_data.storage_version = 1;
+        }
+
+        // sets the code of the contract back to `code_hash`
+        #[ink(message)]
+        pub fn upgrade(&mut self, code_hash: [u8; 32]) {
+            ink::env::set_code_hash(&code_hash).expect("set_code_hash failed")
+        }
+    }
+}
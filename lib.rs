@@ -5,11 +5,32 @@
 mod contract {
     use ink::codegen::EmitEvent;
     use ink::codegen::Env;
+    use ink::prelude::string::String;
     use logics::{
-        impls::room_book::{room_book::HotelRoomBookingEvents, types::RoomId, *},
+        impls::room_book::{
+            room_book::HotelRoomBookingEvents,
+            types::{
+                ContentId, Currency, LateFeePolicy, LeaseTerms, ProposalId, RoomId,
+                VerificationStatus,
+            },
+            *,
+        },
         traits::room_book::*,
     };
-    use openbrush::{contracts::ownable::*, traits::Storage};
+    use openbrush::{
+        contracts::{
+            access_control::{self, AccessControl},
+            ownable::*,
+            pausable::{self, Pausable},
+            psp34::{
+                self,
+                extensions::metadata::{self, PSP34Metadata},
+                PSP34,
+            },
+            reentrancy_guard,
+        },
+        traits::Storage,
+    };
 
     #[ink(storage)]
     #[derive(Storage, Default)]
@@ -17,11 +38,46 @@ mod contract {
         #[storage_field]
         ownable: ownable::Data,
         #[storage_field]
+        access: access_control::Data,
+        #[storage_field]
+        pause: pausable::Data,
+        #[storage_field]
+        guard: reentrancy_guard::Data,
+        #[storage_field]
         hotel_data: types::Data,
+        #[storage_field]
+        psp34: psp34::Data,
+        #[storage_field]
+        metadata: metadata::Data,
     }
 
     impl RoomBook for Hotel {}
 
+    impl AccessControl for Hotel {}
+
+    impl Pausable for Hotel {}
+
+    impl PSP34 for Hotel {}
+
+    impl PSP34Metadata for Hotel {}
+
+    impl psp34::Transfer for Hotel {
+        // lease token is the proof of tenancy of its tenant, it is only minted and burned
+        fn _before_token_transfer(
+            &mut self,
+            from: Option<&AccountId>,
+            to: Option<&AccountId>,
+            _id: &psp34::Id,
+        ) -> Result<(), psp34::PSP34Error> {
+            if from.is_some() && to.is_some() {
+                return Err(psp34::PSP34Error::Custom(String::from(
+                    "LeaseNotTransferable",
+                )));
+            }
+            Ok(())
+        }
+    }
+
     #[ink(event)]
     pub struct AddRoomEvent {
         #[ink(topic)]
@@ -36,68 +92,2415 @@ mod contract {
         room_id: RoomId,
         #[ink(topic)]
         agreement_signer: AccountId,
+        credited: Balance,
+    }
+
+    #[ink(event)]
+    pub struct RentPaymentEvent {
+        #[ink(topic)]
+        room_id: RoomId,
+        #[ink(topic)]
+        rent_payment_signer: AccountId,
+        credited: Balance,
+    }
+
+    #[ink(event)]
+    pub struct AgreementCompletedEvent {
+        #[ink(topic)]
+        room_id: RoomId,
+    }
+
+    #[ink(event)]
+    pub struct AgreementTerminatedEvent {
+        #[ink(topic)]
+        room_id: RoomId,
+    }
+
+    #[ink(event)]
+    pub struct AgreementExitedEvent {
+        #[ink(topic)]
+        room_id: RoomId,
+        #[ink(topic)]
+        tenant: AccountId,
+        penalty: Balance,
+    }
+
+    #[ink(event)]
+    pub struct ClaimCreditedEvent {
+        #[ink(topic)]
+        account: AccountId,
+        amount: Balance,
+        currency: Currency,
+    }
+
+    #[ink(event)]
+    pub struct WithdrawalEvent {
+        #[ink(topic)]
+        account: AccountId,
+        amount: Balance,
+        currency: Currency,
+    }
+
+    #[ink(event)]
+    pub struct DepositDeductedEvent {
+        #[ink(topic)]
+        room_id: RoomId,
+        amount: Balance,
+    }
+
+    #[ink(event)]
+    pub struct LandlordRegisteredEvent {
+        #[ink(topic)]
+        landlord: AccountId,
+    }
+
+    #[ink(event)]
+    pub struct LandlordRemovedEvent {
+        #[ink(topic)]
+        landlord: AccountId,
+    }
+
+    #[ink(event)]
+    pub struct TenantAttestedEvent {
+        #[ink(topic)]
+        account: AccountId,
+        status: VerificationStatus,
+        expiry: Timestamp,
+    }
+
+    #[ink(event)]
+    pub struct TenantBannedEvent {
+        #[ink(topic)]
+        account: AccountId,
+        reason_code: u32,
+        expiry: Option<Timestamp>,
+    }
+
+    #[ink(event)]
+    pub struct TenantUnbannedEvent {
+        #[ink(topic)]
+        account: AccountId,
+    }
+
+    #[ink(event)]
+    pub struct LandlordProposedEvent {
+        #[ink(topic)]
+        landlord: AccountId,
+        #[ink(topic)]
+        proposed: AccountId,
+    }
+
+    #[ink(event)]
+    pub struct LandlordshipTransferredEvent {
+        #[ink(topic)]
+        previous: AccountId,
+        #[ink(topic)]
+        new: AccountId,
+    }
+
+    #[ink(event)]
+    pub struct ProposalCreatedEvent {
+        #[ink(topic)]
+        proposal_id: ProposalId,
+        #[ink(topic)]
+        proposer: AccountId,
+    }
+
+    #[ink(event)]
+    pub struct ProposalApprovedEvent {
+        #[ink(topic)]
+        proposal_id: ProposalId,
+        #[ink(topic)]
+        signer: AccountId,
+    }
+
+    #[ink(event)]
+    pub struct ProposalExecutedEvent {
+        #[ink(topic)]
+        proposal_id: ProposalId,
+    }
+
+    #[ink(event)]
+    pub struct RentChangeScheduled {
+        #[ink(topic)]
+        room_id: RoomId,
+        rent_per_month: Balance,
+        security_deposit: Balance,
+        effective_at: Timestamp,
+    }
+
+    #[ink(event)]
+    pub struct ContractUpgradedEvent {
+        code_hash: [u8; 32],
+    }
+
+    #[ink(event)]
+    pub struct StorageMigratedEvent {
+        from_version: u32,
+        to_version: u32,
+    }
+
+    #[ink(event)]
+    pub struct SurplusWithdrawnEvent {
+        #[ink(topic)]
+        account: AccountId,
+        amount: Balance,
+        contract_balance: Balance,
+        liabilities: Balance,
+    }
+
+    #[ink(event)]
+    pub struct RoomUpdated {
+        #[ink(topic)]
+        room_id: RoomId,
+    }
+
+    #[ink(event)]
+    pub struct RoomRemoved {
+        #[ink(topic)]
+        room_id: RoomId,
+    }
+
+    #[ink(event)]
+    pub struct BillingPeriodChanged {
+        #[ink(topic)]
+        room_id: RoomId,
+        billing_period: Timestamp,
+    }
+
+    #[ink(event)]
+    pub struct LateFeePolicyChanged {
+        #[ink(topic)]
+        room_id: RoomId,
+        policy: LateFeePolicy,
+    }
+
+    #[ink(event)]
+    pub struct LeaseTermsChanged {
+        #[ink(topic)]
+        room_id: RoomId,
+        terms: LeaseTerms,
+    }
+
+    #[ink(event)]
+    pub struct RoomMetadataUpdated {
+        #[ink(topic)]
+        room_id: RoomId,
+        content: ContentId,
+        mime_type: String,
+    }
+
+    #[ink(event)]
+    pub struct OwnershipTransferred {
+        #[ink(topic)]
+        previous_owner: Option<AccountId>,
+        #[ink(topic)]
+        new_owner: Option<AccountId>,
+    }
+
+    #[ink(event)]
+    pub struct Paused {
+        account: AccountId,
+    }
+
+    #[ink(event)]
+    pub struct Unpaused {
+        account: AccountId,
+    }
+
+    impl Hotel {
+        #[ink(constructor, payable)]
+        pub fn new() -> Self {
+            let mut instance = Self::default();
+            let caller = Self::env().caller();
+            instance._init_with_owner(caller);
+            instance.hotel_data.storage_version = types::STORAGE_VERSION;
+            // owner is the admin of the staff roles and holds all of them at deployment
+            access_control::Internal::_init_with_admin(&mut instance, caller);
+            for role in [
+                types::MANAGER,
+                types::ACCOUNTANT,
+                types::FRONT_DESK,
+                types::ATTESTER,
+            ] {
+                instance
+                    .grant_role(role, caller)
+                    .expect("Should grant the role");
+            }
+            instance
+        }
+    }
+
+    impl HotelRoomBookingEvents for Hotel {
+        fn emit_add_room_event(&self, room_id: RoomId, owner: AccountId) {
+            self.env().emit_event(AddRoomEvent { room_id, owner });
+        }
+        fn emit_sign_agreement_event(
+            &self,
+            room_id: RoomId,
+            agreement_signer: AccountId,
+            credited: Balance,
+        ) {
+            self.env().emit_event(SignAgreementEvent {
+                room_id,
+                agreement_signer,
+                credited,
+            });
+        }
+        fn emit_rent_payment_event(
+            &self,
+            room_id: RoomId,
+            rent_payment_signer: AccountId,
+            credited: Balance,
+        ) {
+            self.env().emit_event(RentPaymentEvent {
+                room_id,
+                rent_payment_signer,
+                credited,
+            });
+        }
+        fn emit_agreement_complete_event(&self, room_id: RoomId) {
+            self.env().emit_event(AgreementCompletedEvent { room_id });
+        }
+        fn emit_agreement_terminated_event(&self, room_id: RoomId) {
+            self.env().emit_event(AgreementTerminatedEvent { room_id });
+        }
+        fn emit_agreement_exited_event(
+            &self,
+            room_id: RoomId,
+            tenant: AccountId,
+            penalty: Balance,
+        ) {
+            self.env().emit_event(AgreementExitedEvent {
+                room_id,
+                tenant,
+                penalty,
+            });
+        }
+        fn emit_claim_credited_event(
+            &self,
+            account: AccountId,
+            amount: Balance,
+            currency: Currency,
+        ) {
+            self.env().emit_event(ClaimCreditedEvent {
+                account,
+                amount,
+                currency,
+            });
+        }
+        fn emit_withdrawal_event(&self, account: AccountId, amount: Balance, currency: Currency) {
+            self.env().emit_event(WithdrawalEvent {
+                account,
+                amount,
+                currency,
+            });
+        }
+        fn emit_deposit_deducted_event(&self, room_id: RoomId, amount: Balance) {
+            self.env()
+                .emit_event(DepositDeductedEvent { room_id, amount });
+        }
+        fn emit_landlord_registered_event(&self, landlord: AccountId) {
+            self.env().emit_event(LandlordRegisteredEvent { landlord });
+        }
+        fn emit_landlord_removed_event(&self, landlord: AccountId) {
+            self.env().emit_event(LandlordRemovedEvent { landlord });
+        }
+        fn emit_tenant_attested_event(
+            &self,
+            account: AccountId,
+            status: VerificationStatus,
+            expiry: Timestamp,
+        ) {
+            self.env().emit_event(TenantAttestedEvent {
+                account,
+                status,
+                expiry,
+            });
+        }
+        fn emit_tenant_banned_event(
+            &self,
+            account: AccountId,
+            reason_code: u32,
+            expiry: Option<Timestamp>,
+        ) {
+            self.env().emit_event(TenantBannedEvent {
+                account,
+                reason_code,
+                expiry,
+            });
+        }
+        fn emit_tenant_unbanned_event(&self, account: AccountId) {
+            self.env().emit_event(TenantUnbannedEvent { account });
+        }
+        fn emit_landlord_proposed_event(&self, landlord: AccountId, proposed: AccountId) {
+            self.env()
+                .emit_event(LandlordProposedEvent { landlord, proposed });
+        }
+        fn emit_landlordship_transferred_event(&self, previous: AccountId, new: AccountId) {
+            self.env()
+                .emit_event(LandlordshipTransferredEvent { previous, new });
+        }
+        fn emit_proposal_created_event(&self, proposal_id: ProposalId, proposer: AccountId) {
+            self.env().emit_event(ProposalCreatedEvent {
+                proposal_id,
+                proposer,
+            });
+        }
+        fn emit_proposal_approved_event(&self, proposal_id: ProposalId, signer: AccountId) {
+            self.env().emit_event(ProposalApprovedEvent {
+                proposal_id,
+                signer,
+            });
+        }
+        fn emit_proposal_executed_event(&self, proposal_id: ProposalId) {
+            self.env().emit_event(ProposalExecutedEvent { proposal_id });
+        }
+        fn emit_rent_change_scheduled_event(
+            &self,
+            room_id: RoomId,
+            rent_per_month: Balance,
+            security_deposit: Balance,
+            effective_at: Timestamp,
+        ) {
+            self.env().emit_event(RentChangeScheduled {
+                room_id,
+                rent_per_month,
+                security_deposit,
+                effective_at,
+            });
+        }
+        fn emit_contract_upgraded_event(&self, code_hash: [u8; 32]) {
+            self.env().emit_event(ContractUpgradedEvent { code_hash });
+        }
+        fn emit_storage_migrated_event(&self, from_version: u32, to_version: u32) {
+            self.env().emit_event(StorageMigratedEvent {
+                from_version,
+                to_version,
+            });
+        }
+        fn emit_surplus_withdrawn_event(
+            &self,
+            account: AccountId,
+            amount: Balance,
+            contract_balance: Balance,
+            liabilities: Balance,
+        ) {
+            self.env().emit_event(SurplusWithdrawnEvent {
+                account,
+                amount,
+                contract_balance,
+                liabilities,
+            });
+        }
+        fn emit_room_updated_event(&self, room_id: RoomId) {
+            self.env().emit_event(RoomUpdated { room_id });
+        }
+        fn emit_room_removed_event(&self, room_id: RoomId) {
+            self.env().emit_event(RoomRemoved { room_id });
+        }
+        fn emit_billing_period_changed_event(&self, room_id: RoomId, billing_period: Timestamp) {
+            self.env().emit_event(BillingPeriodChanged {
+                room_id,
+                billing_period,
+            });
+        }
+        fn emit_late_fee_policy_changed_event(&self, room_id: RoomId, policy: LateFeePolicy) {
+            self.env()
+                .emit_event(LateFeePolicyChanged { room_id, policy });
+        }
+        fn emit_lease_terms_changed_event(&self, room_id: RoomId, terms: LeaseTerms) {
+            self.env().emit_event(LeaseTermsChanged { room_id, terms });
+        }
+        fn emit_room_metadata_updated_event(
+            &self,
+            room_id: RoomId,
+            content: ContentId,
+            mime_type: String,
+        ) {
+            self.env().emit_event(RoomMetadataUpdated {
+                room_id,
+                content,
+                mime_type,
+            });
+        }
+    }
+
+    impl ownable::Internal for Hotel {
+        fn _emit_ownership_transferred_event(
+            &self,
+            previous_owner: Option<AccountId>,
+            new_owner: Option<AccountId>,
+        ) {
+            self.env().emit_event(OwnershipTransferred {
+                previous_owner,
+                new_owner,
+            });
+        }
+    }
+
+    impl pausable::Internal for Hotel {
+        fn _emit_paused_event(&self, account: AccountId) {
+            self.env().emit_event(Paused { account });
+        }
+        fn _emit_unpaused_event(&self, account: AccountId) {
+            self.env().emit_event(Unpaused { account });
+        }
     }
 
-    #[ink(event)]
-    pub struct RentPaymentEvent {
-        #[ink(topic)]
-        room_id: RoomId,
-        #[ink(topic)]
-        rent_payment_signer: AccountId,
-    }
+    #[cfg(test)]
+    mod tests {
+        use crate::contract::types::{
+            AgreementId, HotelError, LateFeePolicy, LeaseTerms, ProposalAction, ProposalStatus,
+            RoomAttributes, RoomCategory, RoomFilter, RoomPage, ACCOUNTANT, ATTESTER, BASIS_POINTS,
+            DAY, DEFAULT_BILLING_PERIOD, DEFAULT_NOTICE_PERIOD, FRONT_DESK, MANAGER,
+            MAX_CID_LENGTH, MAX_PAGE_SCAN, MAX_PAGE_SIZE, RENT_PAYMENT_WINDOW, STORAGE_VERSION,
+        };
+        use ink::{
+            env::{test, DefaultEnvironment},
+            storage::traits::StorageKey,
+        };
+        use logics::impls::room_book::migration::{
+            ProposalActionV1, ProposalV1, RoomAgreementV1, RoomV1,
+        };
+        use openbrush::traits::ZERO_ADDRESS;
+
+        use super::*;
+
+        type Event = <Hotel as ::ink::reflect::ContractEventBase>::Type;
+
+        const RENT: Balance = 100;
+        const DEPOSIT: Balance = 50;
+
+        fn accounts() -> test::DefaultAccounts<DefaultEnvironment> {
+            test::default_accounts::<DefaultEnvironment>()
+        }
+
+        fn contract_id() -> AccountId {
+            AccountId::from([0x42; 32])
+        }
+
+        fn set_caller(account: AccountId) {
+            test::set_caller::<DefaultEnvironment>(account);
+        }
+
+        fn set_now(time_stamp: Timestamp) {
+            test::set_block_timestamp::<DefaultEnvironment>(time_stamp);
+        }
+
+        fn balance_of(account: AccountId) -> Balance {
+            test::get_account_balance::<DefaultEnvironment>(account)
+                .expect("account has no balance")
+        }
+
+        // `account` calls the next message with `value`, which lands on the contract
+        fn pay(account: AccountId, value: Balance) {
+            set_caller(account);
+            test::set_value_transferred::<DefaultEnvironment>(value);
+            test::set_account_balance::<DefaultEnvironment>(
+                contract_id(),
+                balance_of(contract_id()) + value,
+            );
+        }
+
+        // hotel of alice with a room renting for `RENT` plus `DEPOSIT`, bob is a verified
+        // tenant
+        fn hotel_with_room() -> (Hotel, RoomId) {
+            test::set_callee::<DefaultEnvironment>(contract_id());
+            test::set_account_balance::<DefaultEnvironment>(contract_id(), 0);
+            set_now(0);
+            set_caller(accounts().alice);
+
+            let mut hotel = Hotel::new();
+            let room_id = hotel
+                .add_room(
+                    String::from("room one"),
+                    String::from("room address"),
+                    RENT,
+                    DEPOSIT,
+                    0,
+                    Default::default(),
+                )
+                .expect("add_room failed");
+            hotel
+                .attest_tenant(
+                    accounts().bob,
+                    VerificationStatus::Verified,
+                    Timestamp::MAX,
+                    [1; 32],
+                )
+                .expect("attest_tenant failed");
+
+            (hotel, room_id)
+        }
+
+        fn add_rooms(hotel: &mut Hotel, count: u32, attributes: RoomAttributes) {
+            set_caller(accounts().alice);
+            for _ in 0..count {
+                hotel
+                    .add_room(
+                        String::from("room name"),
+                        String::from("room address"),
+                        RENT,
+                        DEPOSIT,
+                        0,
+                        attributes.clone(),
+                    )
+                    .expect("add_room failed");
+            }
+        }
+
+        #[ink::test]
+        fn withdraw_pays_out_claims() {
+            // given bob rents the room, the first month is credited to alice
+            let (mut hotel, room_id) = hotel_with_room();
+            let accounts = accounts();
+            pay(accounts.bob, RENT + DEPOSIT);
+            hotel
+                .sign_agreement(room_id)
+                .expect("sign_agreement failed");
+            assert_eq!(hotel.balance_of_claims(accounts.alice), RENT);
+            assert_eq!(hotel.get_reconciliation().claims, RENT);
+
+            // claims are checked before anything is paid out
+            set_caller(accounts.alice);
+            assert_eq!(hotel.withdraw(0), Err(HotelError::InvalidWithdrawAmount));
+            assert_eq!(
+                hotel.withdraw(RENT + 1),
+                Err(HotelError::InsufficientClaimBalance)
+            );
+            set_caller(accounts.charlie);
+            assert_eq!(hotel.withdraw(1), Err(HotelError::InsufficientClaimBalance));
+
+            // when
+            let alice_balance = balance_of(accounts.alice);
+            set_caller(accounts.alice);
+            hotel.withdraw(40).expect("withdraw failed");
+
+            // then the value leaves the contract and the claim shrinks by the same amount
+            assert_eq!(balance_of(accounts.alice), alice_balance + 40);
+            assert_eq!(balance_of(contract_id()), RENT + DEPOSIT - 40);
+            assert_eq!(hotel.balance_of_claims(accounts.alice), RENT - 40);
+            assert_eq!(hotel.get_reconciliation().claims, RENT - 40);
+
+            hotel.withdraw(RENT - 40).expect("withdraw failed");
+            assert_eq!(hotel.balance_of_claims(accounts.alice), 0);
+            assert_eq!(balance_of(contract_id()), DEPOSIT);
+        }
+
+        #[ink::test]
+        fn withdraw_token_checks_token_claims() {
+            // given bob rents the room in a token, booked without pulling the token
+            let (mut hotel, room_id) = hotel_with_room();
+            let accounts = accounts();
+            let token = AccountId::from([0x07; 32]);
+            set_caller(accounts.bob);
+            hotel
+                .book_room(room_id, RENT + DEPOSIT, Currency::Psp22(token))
+                .expect("book_room failed");
+
+            // then the rent is a token claim of alice and the deposit is held in the token
+            assert_eq!(hotel.balance_of_token_claims(token, accounts.alice), RENT);
+            assert_eq!(hotel.balance_of_claims(accounts.alice), 0);
+            assert_eq!(hotel.get_token_escrow(token), DEPOSIT);
+            assert_eq!(hotel.get_reconciliation().claims, 0);
+
+            // and token claims are only paid out in that token
+            set_caller(accounts.alice);
+            assert_eq!(
+                hotel.withdraw(RENT),
+                Err(HotelError::InsufficientClaimBalance)
+            );
+            assert_eq!(
+                hotel.withdraw_token(token, 0),
+                Err(HotelError::InvalidWithdrawAmount)
+            );
+            assert_eq!(
+                hotel.withdraw_token(token, RENT + 1),
+                Err(HotelError::InsufficientClaimBalance)
+            );
+            assert_eq!(
+                hotel.withdraw_token(AccountId::from([0x08; 32]), 1),
+                Err(HotelError::InsufficientClaimBalance)
+            );
+            assert_eq!(hotel.balance_of_token_claims(token, accounts.alice), RENT);
+        }
+
+        #[ink::test]
+        fn deposit_is_held_in_escrow() {
+            // given
+            let (mut hotel, room_id) = hotel_with_room();
+            let accounts = accounts();
+            pay(accounts.bob, RENT + DEPOSIT);
+            hotel
+                .sign_agreement(room_id)
+                .expect("sign_agreement failed");
+
+            // then the deposit is held, nobody can withdraw it
+            assert_eq!(hotel.get_room_deposit(room_id), DEPOSIT);
+            assert_eq!(hotel.get_total_escrow(), DEPOSIT);
+            assert_eq!(hotel.get_reconciliation().held_deposits, DEPOSIT);
+            set_caller(accounts.bob);
+            assert_eq!(hotel.withdraw(1), Err(HotelError::InsufficientClaimBalance));
+
+            // deductions are limited to the held deposit
+            set_caller(accounts.charlie);
+            assert_eq!(
+                hotel.deduct_deposit(room_id, 10),
+                Err(HotelError::MissingAccountantRole)
+            );
+            set_caller(accounts.alice);
+            assert_eq!(
+                hotel.deduct_deposit(room_id, 0),
+                Err(HotelError::InvalidDeductionAmount)
+            );
+            assert_eq!(
+                hotel.deduct_deposit(room_id, DEPOSIT + 1),
+                Err(HotelError::InsufficientDeposit)
+            );
+
+            // when
+            hotel
+                .deduct_deposit(room_id, 20)
+                .expect("deduct_deposit failed");
+
+            // then the deduction moves from escrow to the claims of the landlord
+            assert_eq!(hotel.get_room_deposit(room_id), DEPOSIT - 20);
+            assert_eq!(hotel.get_total_escrow(), DEPOSIT - 20);
+            assert_eq!(hotel.balance_of_claims(accounts.alice), RENT + 20);
+
+            // and the rest goes back to the tenant when the agreement is terminated
+            hotel
+                .agreement_terminated(room_id, None)
+                .expect("agreement_terminated failed");
+            assert_eq!(hotel.get_total_escrow(), 0);
+            assert_eq!(hotel.get_room_deposit(room_id), 0);
+            assert_eq!(hotel.balance_of_claims(accounts.bob), DEPOSIT - 20);
+            assert_eq!(
+                hotel.deduct_deposit(room_id, 1),
+                Err(HotelError::RoomIsVacant)
+            );
+
+            // every held unit is still in the contract
+            let reconciliation = hotel.get_reconciliation();
+            assert_eq!(reconciliation.liabilities, RENT + DEPOSIT);
+            assert_eq!(reconciliation.contract_balance, RENT + DEPOSIT);
+        }
+
+        #[ink::test]
+        fn overpayment_carries_over_to_next_rent() {
+            // given bob overpays the agreement by 30
+            let (mut hotel, room_id) = hotel_with_room();
+            let accounts = accounts();
+            pay(accounts.bob, RENT + DEPOSIT + 30);
+            hotel
+                .sign_agreement(room_id)
+                .expect("sign_agreement failed");
+            assert_eq!(hotel.get_tenant_credit(accounts.bob), 30);
+            assert_eq!(hotel.get_reconciliation().tenant_credits, 30);
+
+            // when the next rent is due the credit covers part of it
+            set_now(DEFAULT_BILLING_PERIOD);
+            assert_eq!(
+                hotel.get_rent_status(room_id).unwrap().amount_due,
+                RENT - 30
+            );
+            pay(accounts.bob, RENT - 31);
+            assert_eq!(
+                hotel.pay_rent(room_id),
+                Err(HotelError::InsufficientRentFee(RENT - 30))
+            );
+            pay(accounts.bob, RENT - 30);
+            hotel.pay_rent(room_id).expect("pay_rent failed");
+
+            // then the credit is used up and the landlord got the full rent twice
+            assert_eq!(hotel.get_tenant_credit(accounts.bob), 0);
+            assert_eq!(hotel.balance_of_claims(accounts.alice), 2 * RENT);
+            assert_eq!(hotel.get_reconciliation().tenant_credits, 0);
+
+            // when bob overpays the next rent and claims the credit
+            set_now(2 * DEFAULT_BILLING_PERIOD);
+            pay(accounts.bob, RENT + 5);
+            hotel.pay_rent(room_id).expect("pay_rent failed");
+            assert_eq!(hotel.get_tenant_credit(accounts.bob), 5);
+            set_caller(accounts.bob);
+            assert_eq!(hotel.claim_tenant_credit(), Ok(5));
+
+            // then it moves to the claims of bob once
+            assert_eq!(hotel.get_tenant_credit(accounts.bob), 0);
+            assert_eq!(hotel.balance_of_claims(accounts.bob), 5);
+            assert_eq!(hotel.claim_tenant_credit(), Err(HotelError::NoTenantCredit));
+            let reconciliation = hotel.get_reconciliation();
+            assert_eq!(reconciliation.tenant_credits, 0);
+            assert_eq!(reconciliation.claims, 3 * RENT + 5);
+        }
+
+        #[ink::test]
+        fn rent_due_date_advances_per_billing_period() {
+            // given
+            let (mut hotel, room_id) = hotel_with_room();
+            let accounts = accounts();
+            pay(accounts.bob, RENT + DEPOSIT);
+            hotel
+                .sign_agreement(room_id)
+                .expect("sign_agreement failed");
+            let first_due = DEFAULT_BILLING_PERIOD;
+            assert_eq!(
+                hotel.get_rent_status(room_id).unwrap().next_rent_due_date,
+                first_due
+            );
+
+            // rent can't be paid before the payment window opens
+            set_now(first_due - RENT_PAYMENT_WINDOW - 1);
+            pay(accounts.bob, RENT);
+            assert_eq!(hotel.pay_rent(room_id), Err(HotelError::RentNotDue));
+
+            // only the tenant pays the rent
+            set_now(first_due - RENT_PAYMENT_WINDOW);
+            pay(accounts.charlie, RENT);
+            assert_eq!(hotel.pay_rent(room_id), Err(HotelError::NotATenantAddress));
+
+            // when the window is open
+            pay(accounts.bob, RENT);
+            hotel.pay_rent(room_id).expect("pay_rent failed");
+
+            // then the due date moves by exactly one billing period
+            let status = hotel.get_rent_status(room_id).unwrap();
+            assert_eq!(
+                status.next_rent_due_date,
+                first_due + DEFAULT_BILLING_PERIOD
+            );
+            assert_eq!(status.amount_due, RENT);
+            assert_eq!(status.days_overdue, 0);
+
+            // and the next rent is not due yet
+            pay(accounts.bob, RENT);
+            assert_eq!(hotel.pay_rent(room_id), Err(HotelError::RentNotDue));
+
+            // a custom billing period is announced and left out of the current agreement
+            set_caller(accounts.alice);
+            hotel
+                .set_billing_period(room_id, 7 * DAY)
+                .expect("set_billing_period failed");
+            let emitted_events = test::recorded_events().collect::<Vec<_>>();
+            let billing_period_changed = emitted_events
+                .iter()
+                .filter_map(|event| <Event as scale::Decode>::decode(&mut &event.data[..]).ok())
+                .any(|event| {
+                    matches!(
+                        event,
+                        Event::BillingPeriodChanged(BillingPeriodChanged {
+                            room_id: changed_room,
+                            billing_period,
+                        }) if changed_room == room_id && billing_period == 7 * DAY
+                    )
+                });
+            assert!(billing_period_changed);
+            set_now(first_due + DEFAULT_BILLING_PERIOD);
+            pay(accounts.bob, RENT);
+            hotel.pay_rent(room_id).expect("pay_rent failed");
+            assert_eq!(
+                hotel.get_rent_status(room_id).unwrap().next_rent_due_date,
+                first_due + 2 * DEFAULT_BILLING_PERIOD
+            );
+            assert_eq!(hotel.balance_of_claims(accounts.alice), 3 * RENT);
+
+            // and applies to the next agreement of the room
+            set_caller(accounts.bob);
+            hotel
+                .exit_agreement(room_id)
+                .expect("exit_agreement failed");
+            pay(accounts.bob, RENT + DEPOSIT);
+            hotel
+                .sign_agreement(room_id)
+                .expect("sign_agreement failed");
+            let agreement_id = hotel.get_agreements_for_room(room_id)[1].agreement_id;
+            assert_eq!(
+                hotel.get_agreement(agreement_id).unwrap().billing_period,
+                7 * DAY
+            );
+            assert_eq!(
+                hotel.get_rent_status(room_id).unwrap().next_rent_due_date,
+                first_due + DEFAULT_BILLING_PERIOD + 7 * DAY
+            );
+        }
+
+        #[ink::test]
+        fn late_fee_accrues_per_started_day() {
+            let (mut hotel, room_id) = hotel_with_room();
+            let accounts = accounts();
+
+            // percentage above 100% a day is rejected
+            assert_eq!(
+                hotel.set_late_fee_policy(
+                    room_id,
+                    LateFeePolicy {
+                        flat_fee_per_day: 0,
+                        percentage_per_day: BASIS_POINTS + 1,
+                        grace_period: 0,
+                    }
+                ),
+                Err(HotelError::InvalidLateFeePolicy)
+            );
+
+            // 2 flat plus 1.5% of the rent, rounded down to 1, after two days of grace
+            hotel
+                .set_late_fee_policy(
+                    room_id,
+                    LateFeePolicy {
+                        flat_fee_per_day: 2,
+                        percentage_per_day: 150,
+                        grace_period: 2 * DAY,
+                    },
+                )
+                .expect("set_late_fee_policy failed");
+
+            // given bob signs with 4 of credit left
+            pay(accounts.bob, RENT + DEPOSIT + 4);
+            hotel
+                .sign_agreement(room_id)
+                .expect("sign_agreement failed");
+            let due = DEFAULT_BILLING_PERIOD;
+
+            // a new policy is announced and left out of the current agreement
+            set_caller(accounts.alice);
+            let policy = LateFeePolicy {
+                flat_fee_per_day: 50,
+                percentage_per_day: 0,
+                grace_period: 0,
+            };
+            hotel
+                .set_late_fee_policy(room_id, policy.clone())
+                .expect("set_late_fee_policy failed");
+            let emitted_events = test::recorded_events().collect::<Vec<_>>();
+            let late_fee_policy_changed = emitted_events
+                .iter()
+                .filter_map(|event| <Event as scale::Decode>::decode(&mut &event.data[..]).ok())
+                .any(|event| {
+                    matches!(
+                        event,
+                        Event::LateFeePolicyChanged(LateFeePolicyChanged {
+                            room_id: changed_room,
+                            policy: ref changed_policy,
+                        }) if changed_room == room_id && *changed_policy == policy
+                    )
+                });
+            assert!(late_fee_policy_changed);
+
+            // no fee until the grace period is over
+            set_now(due + 2 * DAY);
+            assert_eq!(hotel.get_rent_status(room_id).unwrap().amount_due, RENT - 4);
+
+            // every started day costs 3
+            set_now(due + 2 * DAY + 1);
+            assert_eq!(
+                hotel.get_rent_status(room_id).unwrap().amount_due,
+                RENT + 3 - 4
+            );
+            set_now(due + 4 * DAY + 1);
+            let status = hotel.get_rent_status(room_id).unwrap();
+            assert_eq!(status.amount_due, RENT + 9 - 4);
+            assert_eq!(status.days_overdue, 4);
+
+            // when bob pays one short, the error reports what is still required
+            pay(accounts.bob, RENT + 9 - 4 - 1);
+            assert_eq!(
+                hotel.pay_rent(room_id),
+                Err(HotelError::InsufficientRentFee(RENT + 9 - 4))
+            );
+
+            // when
+            pay(accounts.bob, RENT + 9 - 4);
+            hotel.pay_rent(room_id).expect("pay_rent failed");
+
+            // then rent and late fee are credited to the landlord
+            assert_eq!(hotel.balance_of_claims(accounts.alice), 2 * RENT + 9);
+            assert_eq!(hotel.get_tenant_credit(accounts.bob), 0);
+            assert_eq!(hotel.get_reconciliation().tenant_credits, 0);
+        }
+
+        #[ink::test]
+        fn early_exit_penalty_is_capped_at_the_deposit() {
+            // given a penalty twice the deposit during five days of lock-in
+            let (mut hotel, room_id) = hotel_with_room();
+            let accounts = accounts();
+            assert_eq!(
+                hotel.set_lease_terms(
+                    room_id,
+                    LeaseTerms {
+                        lease_duration: 4 * DAY,
+                        lock_in_period: 5 * DAY,
+                        early_exit_penalty: 0,
+                    }
+                ),
+                Err(HotelError::InvalidLeaseTerms)
+            );
+            hotel
+                .set_lease_terms(
+                    room_id,
+                    LeaseTerms {
+                        lease_duration: 10 * DAY,
+                        lock_in_period: 5 * DAY,
+                        early_exit_penalty: 2 * DEPOSIT,
+                    },
+                )
+                .expect("set_lease_terms failed");
+            pay(accounts.bob, RENT + DEPOSIT);
+            hotel
+                .sign_agreement(room_id)
+                .expect("sign_agreement failed");
+
+            // new terms are announced and left out of the current agreement
+            set_caller(accounts.alice);
+            let terms = LeaseTerms {
+                lease_duration: 10 * DAY,
+                lock_in_period: 0,
+                early_exit_penalty: 0,
+            };
+            hotel
+                .set_lease_terms(room_id, terms.clone())
+                .expect("set_lease_terms failed");
+            let emitted_events = test::recorded_events().collect::<Vec<_>>();
+            let lease_terms_changed = emitted_events
+                .iter()
+                .filter_map(|event| <Event as scale::Decode>::decode(&mut &event.data[..]).ok())
+                .any(|event| {
+                    matches!(
+                        event,
+                        Event::LeaseTermsChanged(LeaseTermsChanged {
+                            room_id: changed_room,
+                            terms: ref changed_terms,
+                        }) if changed_room == room_id && *changed_terms == terms
+                    )
+                });
+            assert!(lease_terms_changed);
+
+            // only the tenant can leave
+            set_now(DAY);
+            set_caller(accounts.charlie);
+            assert_eq!(
+                hotel.exit_agreement(room_id),
+                Err(HotelError::NotATenantAddress)
+            );
+
+            // when bob leaves during the lock-in period
+            set_caller(accounts.bob);
+            hotel
+                .exit_agreement(room_id)
+                .expect("exit_agreement failed");
+
+            // then the landlord gets the whole deposit and nothing more
+            assert_eq!(hotel.balance_of_claims(accounts.alice), RENT + DEPOSIT);
+            assert_eq!(hotel.balance_of_claims(accounts.bob), 0);
+            assert_eq!(hotel.get_total_escrow(), 0);
+            assert_eq!(hotel.get_reconciliation().liabilities, RENT + DEPOSIT);
+        }
+
+        #[ink::test]
+        fn exit_after_lock_in_returns_the_deposit() {
+            // given
+            let (mut hotel, room_id) = hotel_with_room();
+            let accounts = accounts();
+            hotel
+                .set_lease_terms(
+                    room_id,
+                    LeaseTerms {
+                        lease_duration: 10 * DAY,
+                        lock_in_period: 5 * DAY,
+                        early_exit_penalty: 20,
+                    },
+                )
+                .expect("set_lease_terms failed");
+            pay(accounts.bob, RENT + DEPOSIT);
+            hotel
+                .sign_agreement(room_id)
+                .expect("sign_agreement failed");
+
+            // the agreement can't be completed before its end date
+            set_now(5 * DAY);
+            set_caller(accounts.alice);
+            assert_eq!(
+                hotel.agreement_completed(room_id),
+                Err(HotelError::AgreementNotEnded)
+            );
+
+            // when bob leaves once the lock-in period is over
+            set_caller(accounts.bob);
+            hotel
+                .exit_agreement(room_id)
+                .expect("exit_agreement failed");
+
+            // then the full deposit goes back to the tenant
+            assert_eq!(hotel.balance_of_claims(accounts.alice), RENT);
+            assert_eq!(hotel.balance_of_claims(accounts.bob), DEPOSIT);
+            assert_eq!(hotel.get_total_escrow(), 0);
+        }
+
+        #[ink::test]
+        fn penalty_below_the_deposit_is_charged_in_full() {
+            // given
+            let (mut hotel, room_id) = hotel_with_room();
+            let accounts = accounts();
+            hotel
+                .set_lease_terms(
+                    room_id,
+                    LeaseTerms {
+                        lease_duration: 10 * DAY,
+                        lock_in_period: 5 * DAY,
+                        early_exit_penalty: 20,
+                    },
+                )
+                .expect("set_lease_terms failed");
+            pay(accounts.bob, RENT + DEPOSIT);
+            hotel
+                .sign_agreement(room_id)
+                .expect("sign_agreement failed");
+
+            // when bob leaves on the last moment of the lock-in period
+            set_now(5 * DAY - 1);
+            set_caller(accounts.bob);
+            hotel
+                .exit_agreement(room_id)
+                .expect("exit_agreement failed");
+
+            // then the deposit is split between the landlord and the tenant
+            assert_eq!(hotel.balance_of_claims(accounts.alice), RENT + 20);
+            assert_eq!(hotel.balance_of_claims(accounts.bob), DEPOSIT - 20);
+            assert_eq!(hotel.get_total_escrow(), 0);
+        }
+
+        #[ink::test]
+        fn exit_settles_overdue_rent_from_the_deposit() {
+            // given bob rents the room for 20 with a deposit of 100 and a late fee of 1 per day
+            let (mut hotel, room_id) = hotel_with_room();
+            let accounts = accounts();
+            hotel
+                .update_room(
+                    room_id,
+                    String::from("room one"),
+                    String::from("room address"),
+                    20,
+                    100,
+                    Default::default(),
+                )
+                .expect("update_room failed");
+            hotel
+                .set_late_fee_policy(
+                    room_id,
+                    LateFeePolicy {
+                        flat_fee_per_day: 1,
+                        percentage_per_day: 0,
+                        grace_period: 0,
+                    },
+                )
+                .expect("set_late_fee_policy failed");
+            pay(accounts.bob, 20 + 100);
+            hotel
+                .sign_agreement(room_id)
+                .expect("sign_agreement failed");
+
+            // when bob leaves two days after the second rent was due
+            set_now(DEFAULT_BILLING_PERIOD + 2 * DAY);
+            set_caller(accounts.bob);
+            hotel
+                .exit_agreement(room_id)
+                .expect("exit_agreement failed");
+
+            // then the landlord is paid the rent and late fee out of the deposit
+            assert_eq!(hotel.balance_of_claims(accounts.alice), 20 + 20 + 2);
+            assert_eq!(hotel.balance_of_claims(accounts.bob), 100 - 22);
+            assert_eq!(hotel.get_total_escrow(), 0);
+        }
+
+        #[ink::test]
+        fn lease_token_is_not_transferable() {
+            // given
+            let (mut hotel, room_id) = hotel_with_room();
+            let accounts = accounts();
+            pay(accounts.bob, RENT + DEPOSIT);
+            hotel
+                .sign_agreement(room_id)
+                .expect("sign_agreement failed");
+            let agreement_id = hotel.get_agreements_for_room(room_id)[0].agreement_id;
+            let id = psp34::Id::U32(agreement_id as u32);
+            assert_eq!(PSP34::owner_of(&hotel, id.clone()), Some(accounts.bob));
+
+            // when the tenant or an approved operator transfers the lease
+            set_caller(accounts.bob);
+            let not_transferable = Err(psp34::PSP34Error::Custom(String::from(
+                "LeaseNotTransferable",
+            )));
+            assert_eq!(
+                PSP34::transfer(&mut hotel, accounts.charlie, id.clone(), Vec::new()),
+                not_transferable
+            );
+            PSP34::approve(&mut hotel, accounts.charlie, Some(id.clone()), true)
+                .expect("approve failed");
+            set_caller(accounts.charlie);
+            assert_eq!(
+                PSP34::transfer(&mut hotel, accounts.charlie, id.clone(), Vec::new()),
+                not_transferable
+            );
+
+            // then the tenant keeps it until the agreement ends
+            assert_eq!(PSP34::owner_of(&hotel, id.clone()), Some(accounts.bob));
+            set_caller(accounts.bob);
+            hotel
+                .exit_agreement(room_id)
+                .expect("exit_agreement failed");
+            assert_eq!(PSP34::owner_of(&hotel, id), None);
+        }
+
+        #[ink::test]
+        fn roles_only_act_on_rooms_of_the_hotel() {
+            // given django is a registered landlord with a room and charlie is
+            // a `MANAGER`
+            let (mut hotel, hotel_room) = hotel_with_room();
+            let accounts = accounts();
+            hotel
+                .register_landlord(accounts.django)
+                .expect("register_landlord failed");
+            AccessControl::grant_role(&mut hotel, MANAGER, accounts.charlie)
+                .expect("grant_role failed");
+            set_caller(accounts.django);
+            let landlord_room = hotel
+                .add_room(
+                    String::from("room two"),
+                    String::from("room address"),
+                    RENT,
+                    DEPOSIT,
+                    0,
+                    Default::default(),
+                )
+                .expect("add_room failed");
+
+            // then the staff manages the rooms of the hotel
+            set_caller(accounts.charlie);
+            assert_eq!(
+                hotel.set_billing_period(hotel_room, 7 * DAY),
+                Ok(hotel_room)
+            );
+
+            // but not the rooms of a registered landlord, not even the owner
+            for account in [accounts.charlie, accounts.alice] {
+                set_caller(account);
+                assert_eq!(
+                    hotel.set_billing_period(landlord_room, 7 * DAY),
+                    Err(HotelError::CallerIsNotRoomLandlord)
+                );
+                assert_eq!(
+                    hotel.delist_room(landlord_room),
+                    Err(HotelError::CallerIsNotRoomLandlord)
+                );
+            }
+
+            // accounts without the role can't manage the rooms of the hotel
+            set_caller(accounts.eve);
+            assert_eq!(
+                hotel.set_billing_period(hotel_room, 7 * DAY),
+                Err(HotelError::MissingManagerRole)
+            );
+
+            // and the landlord manages that room without a role
+            set_caller(accounts.django);
+            assert_eq!(
+                hotel.set_billing_period(landlord_room, 7 * DAY),
+                Ok(landlord_room)
+            );
+        }
+
+        #[ink::test]
+        fn handover_moves_every_privilege_of_the_owner() {
+            // given bob rents a room of the hotel
+            let (mut hotel, room_id) = hotel_with_room();
+            let accounts = accounts();
+            pay(accounts.bob, RENT + DEPOSIT);
+            hotel
+                .sign_agreement(room_id)
+                .expect("sign_agreement failed");
+
+            // when alice hands the hotel over to charlie
+            set_caller(accounts.alice);
+            hotel
+                .propose_new_landlord(accounts.charlie)
+                .expect("propose_new_landlord failed");
+            set_caller(accounts.charlie);
+            hotel
+                .accept_landlordship()
+                .expect("accept_landlordship failed");
+
+            // then the ownable event is emitted
+            let emitted_events = test::recorded_events().collect::<Vec<_>>();
+            let ownership_transferred = emitted_events
+                .iter()
+                .filter_map(|event| <Event as scale::Decode>::decode(&mut &event.data[..]).ok())
+                .any(|event| {
+                    matches!(
+                        event,
+                        Event::OwnershipTransferred(OwnershipTransferred {
+                            previous_owner: Some(previous_owner),
+                            new_owner: Some(new_owner),
+                        }) if previous_owner == accounts.alice && new_owner == accounts.charlie
+                    )
+                });
+            assert!(ownership_transferred);
+
+            // and every role moved to charlie
+            for role in [
+                access_control::DEFAULT_ADMIN_ROLE,
+                MANAGER,
+                ACCOUNTANT,
+                FRONT_DESK,
+                ATTESTER,
+            ] {
+                assert!(!hotel.has_role(role, accounts.alice));
+                assert!(hotel.has_role(role, accounts.charlie));
+            }
+            // registered landlords are not part of the handover
+            assert!(!hotel.is_landlord(accounts.alice));
+            assert!(!hotel.is_landlord(accounts.charlie));
+
+            // and the rooms of the hotel follow the owner
+            assert!(hotel
+                .get_rooms_by_landlord(accounts.alice, 0, MAX_PAGE_SIZE)
+                .is_empty());
+            let rooms = hotel.get_rooms_by_landlord(accounts.charlie, 0, MAX_PAGE_SIZE);
+            assert_eq!(rooms.len(), 1);
+            assert_eq!(rooms[0].room.landlord, accounts.charlie);
+
+            // alice can't manage the hotel anymore
+            set_caller(accounts.alice);
+            assert_eq!(
+                hotel.set_billing_period(room_id, 7 * DAY),
+                Err(HotelError::MissingManagerRole)
+            );
+            assert_eq!(
+                hotel.agreement_terminated(room_id, None),
+                Err(HotelError::MissingFrontDeskRole)
+            );
+            assert_eq!(
+                hotel.deduct_deposit(room_id, 1),
+                Err(HotelError::MissingAccountantRole)
+            );
+            assert_eq!(
+                hotel.add_room(
+                    String::from("room two"),
+                    String::from("room address"),
+                    RENT,
+                    DEPOSIT,
+                    0,
+                    Default::default(),
+                ),
+                Err(HotelError::MissingManagerRole)
+            );
+            assert_eq!(
+                hotel.register_landlord(accounts.alice),
+                Err(HotelError::OwnableError(OwnableError::CallerIsNotOwner))
+            );
+            assert_eq!(
+                AccessControl::grant_role(&mut hotel, MANAGER, accounts.alice),
+                Err(access_control::AccessControlError::MissingRole)
+            );
+
+            // and the next rent goes to charlie, alice keeps the rent of the first month
+            set_now(DEFAULT_BILLING_PERIOD);
+            pay(accounts.bob, RENT);
+            hotel.pay_rent(room_id).expect("pay_rent failed");
+            assert_eq!(hotel.balance_of_claims(accounts.alice), RENT);
+            assert_eq!(hotel.balance_of_claims(accounts.charlie), RENT);
+        }
+
+        // hotel with bob renting its room and alice, charlie and django as signers, two of
+        // them are needed to execute a proposal
+        fn hotel_with_multisig() -> (Hotel, RoomId, AgreementId) {
+            let (mut hotel, room_id) = hotel_with_room();
+            let accounts = accounts();
+            hotel
+                .set_multisig(vec![accounts.alice, accounts.charlie, accounts.django], 2)
+                .expect("set_multisig failed");
+            pay(accounts.bob, RENT + DEPOSIT);
+            hotel
+                .sign_agreement(room_id)
+                .expect("sign_agreement failed");
+            let agreement_id = hotel.get_agreements_for_room(room_id)[0].agreement_id;
+
+            (hotel, room_id, agreement_id)
+        }
+
+        #[ink::test]
+        fn proposal_executes_at_the_threshold() {
+            // given
+            let (mut hotel, room_id, agreement_id) = hotel_with_multisig();
+            let accounts = accounts();
+            let action = ProposalAction::ForfeitDeposit {
+                room_id,
+                agreement_id,
+                amount: 20,
+            };
+            set_caller(accounts.bob);
+            assert_eq!(
+                hotel.create_proposal(action.clone(), DAY),
+                Err(HotelError::CallerIsNotSigner)
+            );
+
+            // when alice proposes, the approval of the proposer alone is below the threshold
+            set_caller(accounts.alice);
+            let proposal_id = hotel
+                .create_proposal(action, DAY)
+                .expect("create_proposal failed");
+            assert_eq!(
+                hotel.approve_proposal(proposal_id),
+                Err(HotelError::ProposalAlreadyApproved)
+            );
+            assert_eq!(hotel.get_room_deposit(room_id), DEPOSIT);
+
+            // then the second approval executes it
+            set_caller(accounts.charlie);
+            hotel
+                .approve_proposal(proposal_id)
+                .expect("approve_proposal failed");
+            assert_eq!(hotel.get_room_deposit(room_id), DEPOSIT - 20);
+            assert_eq!(hotel.balance_of_claims(accounts.alice), RENT + 20);
+            assert_eq!(
+                hotel.get_proposal(proposal_id).unwrap().status,
+                ProposalStatus::Executed
+            );
+
+            // and it runs only once
+            set_caller(accounts.django);
+            assert_eq!(
+                hotel.approve_proposal(proposal_id),
+                Err(HotelError::ProposalNotPending)
+            );
+            assert_eq!(hotel.get_room_deposit(room_id), DEPOSIT - 20);
+        }
+
+        #[ink::test]
+        fn proposal_expires_at_its_deadline() {
+            // given
+            let (mut hotel, room_id, agreement_id) = hotel_with_multisig();
+            let accounts = accounts();
+            set_caller(accounts.alice);
+            assert_eq!(
+                hotel.create_proposal(
+                    ProposalAction::TerminateAgreement {
+                        room_id,
+                        agreement_id
+                    },
+                    0
+                ),
+                Err(HotelError::InvalidProposalDeadline)
+            );
+            let proposal_id = hotel
+                .create_proposal(
+                    ProposalAction::TerminateAgreement {
+                        room_id,
+                        agreement_id,
+                    },
+                    DAY,
+                )
+                .expect("create_proposal failed");
+
+            // when the deadline has passed
+            set_now(DAY + 1);
+            set_caller(accounts.charlie);
+
+            // then the proposal can't be approved anymore
+            assert_eq!(
+                hotel.approve_proposal(proposal_id),
+                Err(HotelError::ProposalExpired)
+            );
+            assert_eq!(
+                hotel.get_proposal(proposal_id).unwrap().status,
+                ProposalStatus::Expired
+            );
+            assert_eq!(
+                hotel
+                    .get_rooms_by_tenant(accounts.bob, 0, MAX_PAGE_SIZE)
+                    .len(),
+                1
+            );
+        }
+
+        #[ink::test]
+        fn proposals_are_paged() {
+            // given one proposal more than fits on a page
+            let (mut hotel, room_id, _) = hotel_with_multisig();
+            set_caller(accounts().alice);
+            for rent_per_month in 0..MAX_PAGE_SIZE + 1 {
+                hotel
+                    .create_proposal(
+                        ProposalAction::ChangeRent {
+                            room_id,
+                            rent_per_month: rent_per_month.into(),
+                            security_deposit: DEPOSIT,
+                        },
+                        DAY,
+                    )
+                    .expect("create_proposal failed");
+            }
+
+            // when
+            let first = hotel.get_proposals(0, u32::MAX);
+            let last = hotel.get_proposals(first.next.expect("no next page"), 10);
+
+            // then pages are capped and continue at `next`, oldest first
+            assert_eq!(first.proposals.len(), MAX_PAGE_SIZE as usize);
+            assert_eq!(first.proposals[0].proposal_id, 0);
+            assert_eq!(first.next, Some(MAX_PAGE_SIZE as ProposalId));
+            assert_eq!(last.proposals.len(), 1);
+            assert_eq!(last.proposals[0].proposal_id, MAX_PAGE_SIZE as ProposalId);
+            assert_eq!(last.next, None);
+            assert_eq!(hotel.get_proposals(0, 0), Default::default());
+        }
+
+        #[ink::test]
+        fn proposal_on_a_past_agreement_is_stale() {
+            // given a proposal to terminate the agreement of bob
+            let (mut hotel, room_id, agreement_id) = hotel_with_multisig();
+            let accounts = accounts();
+            let action = ProposalAction::TerminateAgreement {
+                room_id,
+                agreement_id,
+            };
+            set_caller(accounts.alice);
+            let proposal_id = hotel
+                .create_proposal(action.clone(), DAY)
+                .expect("create_proposal failed");
+
+            // when bob leaves and signs a new agreement before the proposal is approved
+            set_caller(accounts.bob);
+            hotel
+                .exit_agreement(room_id)
+                .expect("exit_agreement failed");
+            set_caller(accounts.charlie);
+            assert_eq!(
+                hotel.approve_proposal(proposal_id),
+                Err(HotelError::StaleProposal)
+            );
+            pay(accounts.bob, RENT + DEPOSIT);
+            hotel
+                .sign_agreement(room_id)
+                .expect("sign_agreement failed");
+
+            // then the proposal doesn't touch the new agreement
+            set_caller(accounts.charlie);
+            assert_eq!(
+                hotel.approve_proposal(proposal_id),
+                Err(HotelError::StaleProposal)
+            );
+            assert_eq!(hotel.get_room_deposit(room_id), DEPOSIT);
+            assert_eq!(
+                hotel
+                    .get_rooms_by_tenant(accounts.bob, 0, MAX_PAGE_SIZE)
+                    .len(),
+                1
+            );
+
+            // and no new proposal can be made on it
+            set_caller(accounts.alice);
+            assert_eq!(
+                hotel.create_proposal(action, DAY),
+                Err(HotelError::StaleProposal)
+            );
+        }
+
+        #[ink::test]
+        fn proposal_only_targets_rooms_of_the_hotel() {
+            // given a room of a registered landlord
+            let (mut hotel, _, _) = hotel_with_multisig();
+            let accounts = accounts();
+            set_caller(accounts.alice);
+            hotel
+                .register_landlord(accounts.eve)
+                .expect("register_landlord failed");
+            set_caller(accounts.eve);
+            let room_id = hotel
+                .add_room(
+                    String::from("room two"),
+                    String::from("room address"),
+                    RENT,
+                    DEPOSIT,
+                    0,
+                    Default::default(),
+                )
+                .expect("add_room failed");
+
+            // then signers can't propose anything on it
+            set_caller(accounts.alice);
+            assert_eq!(
+                hotel.create_proposal(
+                    ProposalAction::ChangeRent {
+                        room_id,
+                        rent_per_month: 1,
+                        security_deposit: 1,
+                    },
+                    DAY,
+                ),
+                Err(HotelError::NotAHotelRoom)
+            );
+        }
+
+        #[ink::test]
+        fn multisig_is_required_for_rooms_of_the_hotel() {
+            // given
+            let (mut hotel, room_id, agreement_id) = hotel_with_multisig();
+            let accounts = accounts();
+            set_caller(accounts.alice);
+
+            // when the owner acts directly on a room of the hotel
+            let effective_at = test::get_block_timestamp() + DEFAULT_NOTICE_PERIOD;
+
+            // then each action needs a proposal
+            assert_eq!(
+                hotel.schedule_price_change(room_id, RENT * 2, DEPOSIT, effective_at),
+                Err(HotelError::ProposalRequired)
+            );
+            assert_eq!(
+                hotel.update_room(
+                    room_id,
+                    String::from("room one"),
+                    String::from("room address"),
+                    RENT * 2,
+                    DEPOSIT,
+                    Default::default(),
+                ),
+                Err(HotelError::ProposalRequired)
+            );
+            assert_eq!(
+                hotel.deduct_deposit(room_id, 20),
+                Err(HotelError::ProposalRequired)
+            );
+            assert_eq!(
+                hotel.agreement_terminated(room_id, None),
+                Err(HotelError::ProposalRequired)
+            );
+            assert_eq!(hotel.get_room_deposit(room_id), DEPOSIT);
+            assert_eq!(hotel.get_price_change(room_id), None);
+
+            // details other than the prices are still changed directly
+            hotel
+                .update_room(
+                    room_id,
+                    String::from("renamed room"),
+                    String::from("room address"),
+                    RENT,
+                    DEPOSIT,
+                    Default::default(),
+                )
+                .expect("update_room failed");
+
+            // and the same actions go through once the signers approve them
+            let proposal_id = hotel
+                .create_proposal(
+                    ProposalAction::TerminateAgreement {
+                        room_id,
+                        agreement_id,
+                    },
+                    DAY,
+                )
+                .expect("create_proposal failed");
+            set_caller(accounts.charlie);
+            hotel
+                .approve_proposal(proposal_id)
+                .expect("approve_proposal failed");
+            assert!(hotel.hotel_data.room.get(&room_id).unwrap().vacant);
+            assert_eq!(
+                hotel.hotel_data.room.get(&room_id).unwrap().room_name,
+                "renamed room"
+            );
+        }
+
+        #[ink::test]
+        fn notice_period_has_a_minimum() {
+            let (mut hotel, _) = hotel_with_room();
+            assert_eq!(
+                hotel.set_notice_period(DEFAULT_NOTICE_PERIOD - 1),
+                Err(HotelError::NoticePeriodTooShort)
+            );
+            assert_eq!(hotel.get_notice_period(), DEFAULT_NOTICE_PERIOD);
+
+            hotel
+                .set_notice_period(DEFAULT_NOTICE_PERIOD)
+                .expect("set_notice_period failed");
+            assert_eq!(hotel.get_notice_period(), DEFAULT_NOTICE_PERIOD);
+        }
+
+        #[ink::test]
+        fn price_change_waits_for_the_notice_period() {
+            // given bob rents the room and the notice period is 45 days
+            let (mut hotel, room_id) = hotel_with_room();
+            let accounts = accounts();
+            hotel
+                .set_notice_period(45 * DAY)
+                .expect("set_notice_period failed");
+            pay(accounts.bob, RENT + DEPOSIT);
+            hotel
+                .sign_agreement(room_id)
+                .expect("sign_agreement failed");
+
+            // a change with less notice is rejected
+            set_caller(accounts.alice);
+            assert_eq!(
+                hotel.schedule_price_change(room_id, 2 * RENT, 80, 45 * DAY - 1),
+                Err(HotelError::NoticePeriodTooShort)
+            );
+
+            // when
+            hotel
+                .schedule_price_change(room_id, 2 * RENT, 80, 45 * DAY)
+                .expect("schedule_price_change failed");
+
+            // then the cycle starting before the change keeps the old rent
+            assert_eq!(
+                hotel.get_price_change(room_id).unwrap().effective_at,
+                45 * DAY
+            );
+            set_now(DEFAULT_BILLING_PERIOD);
+            assert_eq!(hotel.get_rent_status(room_id).unwrap().amount_due, RENT);
+            pay(accounts.bob, RENT);
+            hotel.pay_rent(room_id).expect("pay_rent failed");
+            assert!(hotel.get_price_change(room_id).is_some());
+
+            // and the first cycle starting after it pays the new rent
+            set_now(2 * DEFAULT_BILLING_PERIOD);
+            assert_eq!(hotel.get_rent_status(room_id).unwrap().amount_due, 2 * RENT);
+            pay(accounts.bob, RENT);
+            assert_eq!(
+                hotel.pay_rent(room_id),
+                Err(HotelError::InsufficientRentFee(2 * RENT))
+            );
+            pay(accounts.bob, 2 * RENT);
+            hotel.pay_rent(room_id).expect("pay_rent failed");
+            assert!(hotel.get_price_change(room_id).is_none());
+            assert_eq!(hotel.balance_of_claims(accounts.alice), 4 * RENT);
+
+            // the held deposit stays as it was paid
+            assert_eq!(hotel.get_room_deposit(room_id), DEPOSIT);
+        }
+
+        #[ink::test]
+        fn migrate_converts_version_1_records_in_batches() {
+            // given a hotel storing rooms of version 1, room 1 is rented by bob with
+            // agreement 1, agreement 0 was charlie's
+            test::set_callee::<DefaultEnvironment>(contract_id());
+            set_now(0);
+            let accounts = accounts();
+            set_caller(accounts.alice);
+            let mut hotel = Hotel::new();
+            hotel.hotel_data.storage_version = 1;
+            hotel.hotel_data.room_id = 3;
+            hotel.hotel_data.agreement_id = 2;
+            hotel.hotel_data.rent_id = 1;
+
+            let room_key = hotel.hotel_data.room.key();
+            for room_id in 0..3 {
+                let rented = room_id == 1;
+                let room = RoomV1 {
+                    room_id,
+                    agreement_id: if rented { 1 } else { 0 },
+                    room_name: String::from("room"),
+                    room_address: String::from("room address"),
+                    rent_per_month: RENT,
+                    security_deposit: DEPOSIT,
+                    time_stamp: 0,
+                    vacant: !rented,
+                    landlord: accounts.alice,
+                    current_tenant: if rented {
+                        accounts.bob
+                    } else {
+                        ZERO_ADDRESS.into()
+                    },
+                    next_rent_due_date: if rented { 5 * DAY } else { 0 },
+                    billing_period: 7 * DAY,
+                    late_fee_policy: Default::default(),
+                    lease_terms: Default::default(),
+                };
+                ink::env::set_contract_storage(&(&room_key, &room_id), &room);
+            }
+            let agreement_key = hotel.hotel_data.agreement.key();
+            for (agreement_id, tenant) in [(0, accounts.charlie), (1, accounts.bob)] {
+                let agreement = RoomAgreementV1 {
+                    room_id: 1,
+                    agreement_id,
+                    room_name: String::from("room"),
+                    room_address: String::from("room address"),
+                    rent_per_month: RENT,
+                    security_deposit: DEPOSIT,
+                    lock_in_period: 0,
+                    time_stamp: 0,
+                    start_time: 0,
+                    end_time: DAY,
+                    early_exit_penalty: 0,
+                    tenant,
+                    landlord: accounts.alice,
+                    currency: Currency::Native,
+                };
+                ink::env::set_contract_storage(&(&agreement_key, &agreement_id), &agreement);
+            }
+
+            // rents, claims and tenant credits kept their layout
+            let rent = types::Rent {
+                rent_id: 0,
+                room_id: 1,
+                agreement_id: 1,
+                rent_per_month: RENT,
+                tenant_address: accounts.bob,
+                land_lord_address: accounts.alice,
+                ..Default::default()
+            };
+            hotel.hotel_data.rent.insert(&0, &rent);
+            hotel.hotel_data.claims.insert(&accounts.alice, &RENT);
+            hotel
+                .hotel_data
+                .tenant_credit
+                .insert(&accounts.charlie, &10);
+
+            // storage of version 0 never had an `upgrade` to get here
+            hotel.hotel_data.storage_version = 0;
+            assert_eq!(
+                hotel.migrate(0, 2),
+                Err(HotelError::UnsupportedStorageVersion)
+            );
+            hotel.hotel_data.storage_version = 1;
+
+            // batches must be non-empty and follow each other
+            set_now(DAY);
+            assert_eq!(hotel.migrate(1, 2), Err(HotelError::InvalidMigrationBatch));
+            assert_eq!(hotel.migrate(0, 0), Err(HotelError::InvalidMigrationBatch));
+
+            // when
+            assert_eq!(hotel.migrate(0, 2), Ok(Some(2)));
+            assert_eq!(hotel.get_storage_version(), 1);
+            assert_eq!(hotel.migrate(0, 2), Err(HotelError::InvalidMigrationBatch));
+            assert_eq!(hotel.migrate(2, 2), Ok(None));
+
+            // then the version is bumped once every record is converted
+            assert_eq!(hotel.get_storage_version(), STORAGE_VERSION);
+            assert_eq!(hotel.migrate(2, 2), Err(HotelError::AlreadyMigrated));
+
+            // rooms are counted and indexed, rooms of the owner belong to the hotel
+            assert_eq!(hotel.room_count(), 3);
+            assert_eq!(hotel.available_room_count(), 2);
+            assert_eq!(
+                hotel
+                    .get_rooms_by_landlord(accounts.alice, 0, MAX_PAGE_SIZE)
+                    .len(),
+                3
+            );
+            let rooms = hotel.get_rooms_by_tenant(accounts.bob, 0, MAX_PAGE_SIZE);
+            assert_eq!(rooms.len(), 1);
+            assert_eq!(rooms[0].room.room_id, 1);
+            assert_eq!(rooms[0].room.landlord, accounts.alice);
+            assert_eq!(rooms[0].room.next_rent_due_date, 5 * DAY);
+            assert!(rooms[0].room.listed);
+
+            // agreements keep the terms of their room
+            let agreement = hotel.get_agreement(1).unwrap();
+            assert_eq!(agreement.tenant, accounts.bob);
+            assert_eq!(agreement.billing_period, 7 * DAY);
+
+            // parties of every agreement and rent are counted in the totals
+            let reconciliation = hotel.get_reconciliation();
+            assert_eq!(reconciliation.claims, RENT);
+            assert_eq!(reconciliation.tenant_credits, 10);
+
+            // and the converted rooms work with the current code
+            set_now(5 * DAY);
+            pay(accounts.bob, RENT);
+            hotel.pay_rent(1).expect("pay_rent failed");
+            assert_eq!(hotel.balance_of_claims(accounts.alice), 2 * RENT);
+            assert_eq!(
+                hotel.get_rent_status(1).unwrap().next_rent_due_date,
+                12 * DAY
+            );
+        }
+
+        #[ink::test]
+        fn migrate_binds_proposals_of_version_1_to_the_current_agreement() {
+            // given a hotel of version 1 with room 0 rented by bob under agreement 3 and
+            // two proposals on it
+            test::set_callee::<DefaultEnvironment>(contract_id());
+            set_now(0);
+            let accounts = accounts();
+            set_caller(accounts.alice);
+            let mut hotel = Hotel::new();
+            hotel.hotel_data.storage_version = 1;
+            hotel.hotel_data.room_id = 1;
+            hotel.hotel_data.agreement_id = 4;
+            hotel.hotel_data.proposal_id = 2;
+
+            let room = RoomV1 {
+                room_id: 0,
+                agreement_id: 3,
+                room_name: String::from("room"),
+                room_address: String::from("room address"),
+                rent_per_month: RENT,
+                security_deposit: DEPOSIT,
+                time_stamp: 0,
+                vacant: false,
+                landlord: accounts.alice,
+                current_tenant: accounts.bob,
+                next_rent_due_date: DAY,
+                billing_period: DEFAULT_BILLING_PERIOD,
+                late_fee_policy: Default::default(),
+                lease_terms: Default::default(),
+            };
+            ink::env::set_contract_storage(&(&hotel.hotel_data.room.key(), &0), &room);
+            let proposal_key = hotel.hotel_data.proposals.key();
+            let actions = [
+                ProposalActionV1::TerminateAgreement { room_id: 0 },
+                ProposalActionV1::ChangeRent {
+                    room_id: 0,
+                    rent_per_month: 2 * RENT,
+                },
+            ];
+            for (proposal_id, action) in (0..).zip(actions) {
+                let proposal = ProposalV1 {
+                    proposal_id,
+                    action,
+                    proposer: accounts.alice,
+                    approvals: vec![accounts.alice],
+                    deadline: DAY,
+                    status: Default::default(),
+                    time_stamp: 0,
+                };
+                ink::env::set_contract_storage(&(&proposal_key, &proposal_id), &proposal);
+            }
+
+            // when
+            assert_eq!(hotel.migrate(0, 1), Ok(Some(1)));
+            assert_eq!(hotel.migrate(1, 1), Ok(None));
+
+            // then proposals are read with the current layout, they act on the agreement
+            // of the room and keep its deposit
+            let proposal = hotel.get_proposal(0).expect("proposal not found");
+            assert_eq!(
+                proposal.action,
+                ProposalAction::TerminateAgreement {
+                    room_id: 0,
+                    agreement_id: 3,
+                }
+            );
+            assert_eq!(proposal.approvals, vec![accounts.alice]);
+            assert_eq!(
+                hotel.get_proposal(1).expect("proposal not found").action,
+                ProposalAction::ChangeRent {
+                    room_id: 0,
+                    rent_per_month: 2 * RENT,
+                    security_deposit: DEPOSIT,
+                }
+            );
+        }
+
+        #[ink::test]
+        fn withdraw_surplus_never_pays_out_liabilities() {
+            // given bob holds a deposit and credit, alice a claim and 40 more was sent in
+            let (mut hotel, room_id) = hotel_with_room();
+            let accounts = accounts();
+            pay(accounts.bob, RENT + DEPOSIT + 10);
+            hotel
+                .sign_agreement(room_id)
+                .expect("sign_agreement failed");
+            pay(accounts.django, 40);
+            let reconciliation = hotel.get_reconciliation();
+            assert_eq!(reconciliation.liabilities, RENT + DEPOSIT + 10);
+            assert_eq!(reconciliation.surplus, 40);
+
+            // when anyone but the owner or more than the surplus is withdrawn
+            set_caller(accounts.bob);
+            assert_eq!(
+                hotel.withdraw_surplus(40),
+                Err(HotelError::OwnableError(
+                    openbrush::contracts::ownable::OwnableError::CallerIsNotOwner
+                ))
+            );
+            set_caller(accounts.alice);
+            assert_eq!(
+                hotel.withdraw_surplus(41),
+                Err(HotelError::InsufficientSurplus)
+            );
+            assert_eq!(
+                hotel.withdraw_surplus(0),
+                Err(HotelError::InvalidWithdrawAmount)
+            );
+
+            // then only the surplus leaves the contract
+            let alice_balance = balance_of(accounts.alice);
+            hotel.withdraw_surplus(40).expect("withdraw_surplus failed");
+            assert_eq!(balance_of(accounts.alice), alice_balance + 40);
+            assert_eq!(balance_of(contract_id()), RENT + DEPOSIT + 10);
+            assert_eq!(
+                hotel.withdraw_surplus(1),
+                Err(HotelError::InsufficientSurplus)
+            );
+        }
+
+        #[ink::test]
+        fn migrate_rebuilds_the_totals_before_surplus_is_withdrawn() {
+            // given claims and credits a previous version didn't add to the totals
+            let (mut hotel, room_id) = hotel_with_room();
+            let accounts = accounts();
+            pay(accounts.bob, RENT + DEPOSIT + 10);
+            hotel
+                .sign_agreement(room_id)
+                .expect("sign_agreement failed");
+            pay(accounts.django, 40);
+            hotel.hotel_data.total_claims.set(&0);
+            hotel.hotel_data.total_tenant_credit.set(&0);
+            hotel.hotel_data.storage_version = 0;
+
+            // when surplus is withdrawn before the migration
+            set_caller(accounts.alice);
+            assert_eq!(
+                hotel.withdraw_surplus(40),
+                Err(HotelError::MigrationPending)
+            );
+
+            // then a withdrawal of an account that isn't counted yet leaves the totals alone
+            hotel.withdraw(10).expect("withdraw failed");
+            assert_eq!(hotel.get_reconciliation().claims, 0);
+
+            // when the records are migrated
+            assert_eq!(hotel.migrate(0, 10), Ok(None));
+
+            // then every claim and credit is owed again and only the surplus can leave
+            let reconciliation = hotel.get_reconciliation();
+            assert_eq!(reconciliation.claims, RENT - 10);
+            assert_eq!(reconciliation.tenant_credits, 10);
+            assert_eq!(reconciliation.surplus, 40);
+            assert_eq!(
+                hotel.withdraw_surplus(41),
+                Err(HotelError::InsufficientSurplus)
+            );
+            hotel.withdraw_surplus(40).expect("withdraw_surplus failed");
+
+            // and later changes are tracked
+            hotel.withdraw(RENT - 10).expect("withdraw failed");
+            assert_eq!(hotel.get_reconciliation().claims, 0);
+        }
+
+        #[ink::test]
+        fn migrate_converts_version_1_rooms() {
+            // given rooms stored by version 1, room 0 of the owner and room 1 of django
+            // rented by bob
+            test::set_callee::<DefaultEnvironment>(contract_id());
+            set_now(0);
+            let accounts = accounts();
+            set_caller(accounts.alice);
+            let mut hotel = Hotel::new();
+            hotel.hotel_data.storage_version = 1;
+            hotel.hotel_data.room_id = 2;
+
+            let late_fee_policy = LateFeePolicy {
+                flat_fee_per_day: 2,
+                ..Default::default()
+            };
+            let lease_terms = LeaseTerms {
+                lock_in_period: 60 * DAY,
+                ..Default::default()
+            };
+            let room_key = hotel.hotel_data.room.key();
+            for room_id in 0..2 {
+                let rented = room_id == 1;
+                let room = RoomV1 {
+                    room_id,
+                    agreement_id: 0,
+                    room_name: String::from("room"),
+                    room_address: String::from("room address"),
+                    rent_per_month: RENT,
+                    security_deposit: DEPOSIT,
+                    time_stamp: 0,
+                    vacant: !rented,
+                    landlord: if rented {
+                        accounts.django
+                    } else {
+                        accounts.alice
+                    },
+                    current_tenant: if rented {
+                        accounts.bob
+                    } else {
+                        ZERO_ADDRESS.into()
+                    },
+                    next_rent_due_date: if rented { 5 * DAY } else { 0 },
+                    billing_period: 7 * DAY,
+                    late_fee_policy: late_fee_policy.clone(),
+                    lease_terms: lease_terms.clone(),
+                };
+                ink::env::set_contract_storage(&(&room_key, &room_id), &room);
+            }
+
+            // when
+            assert_eq!(hotel.migrate(0, 1), Ok(Some(1)));
+            assert_eq!(hotel.migrate(1, 1), Ok(None));
+
+            // then the storage version is bumped and the rooms are counted and indexed
+            assert_eq!(hotel.get_storage_version(), STORAGE_VERSION);
+            assert_eq!(hotel.room_count(), 2);
+            assert_eq!(hotel.available_room_count(), 1);
+
+            // terms of version 1 are kept, the room of the owner belongs to the hotel
+            let rooms = hotel.get_rooms_by_landlord(accounts.alice, 0, MAX_PAGE_SIZE);
+            assert_eq!(rooms.len(), 1);
+            assert_eq!(rooms[0].room.landlord, accounts.alice);
+            assert_eq!(rooms[0].room.billing_period, 7 * DAY);
+            assert_eq!(rooms[0].room.late_fee_policy, late_fee_policy);
+            assert_eq!(rooms[0].room.lease_terms, lease_terms);
+            assert!(rooms[0].room.listed);
+            assert_eq!(rooms[0].room.attributes, RoomAttributes::default());
+            assert_eq!(
+                hotel.hotel_data.room.get(&0).unwrap().landlord,
+                contract_id()
+            );
+
+            let rooms = hotel.get_rooms_by_tenant(accounts.bob, 0, MAX_PAGE_SIZE);
+            assert_eq!(rooms.len(), 1);
+            assert_eq!(rooms[0].room.room_id, 1);
+            assert_eq!(rooms[0].room.landlord, accounts.django);
+            assert_eq!(rooms[0].room.next_rent_due_date, 5 * DAY);
+            assert_eq!(
+                hotel
+                    .get_rooms_by_landlord(accounts.django, 0, MAX_PAGE_SIZE)
+                    .len(),
+                1
+            );
+
+            assert_eq!(rooms[0].room.attributes, RoomAttributes::default());
+
+            // and a converted room is read as it is stored now
+            assert_eq!(hotel.migrate(0, 2), Err(HotelError::AlreadyMigrated));
+            assert_eq!(
+                hotel.get_rooms_by_tenant(accounts.bob, 0, MAX_PAGE_SIZE),
+                rooms
+            );
+
+            // when the owner describes the vacant room
+            let attributes = RoomAttributes {
+                category: RoomCategory::Double,
+                max_occupants: 2,
+                ..Default::default()
+            };
+            hotel
+                .update_room(
+                    0,
+                    String::from("room zero"),
+                    String::from("room address"),
+                    RENT,
+                    DEPOSIT,
+                    attributes.clone(),
+                )
+                .expect("update_room failed");
+
+            // then it is found by its attributes
+            let filter = RoomFilter {
+                category: Some(RoomCategory::Double),
+                min_occupants: Some(2),
+                ..Default::default()
+            };
+            let found = hotel.search_rooms(filter, 0, 10).rooms;
+            assert_eq!(found.len(), 1);
+            assert_eq!(found[0].room.attributes, attributes);
+        }
+
+        #[ink::test]
+        fn empty_page_has_no_next_page() {
+            // given
+            let (mut hotel, _) = hotel_with_room();
+            add_rooms(&mut hotel, 2, Default::default());
+
+            // when a page of no rooms is asked for
+            let available = hotel.get_available_rooms_page(1, 0);
+            let found = hotel.search_rooms(Default::default(), 1, 0);
+            let all = hotel.get_rooms_page(1, 0).unwrap();
 
-    #[ink(event)]
-    pub struct AgreementCompletedEvent {
-        #[ink(topic)]
-        room_id: RoomId,
-    }
+            // then it doesn't point back at its own start
+            assert_eq!(available, RoomPage::default());
+            assert_eq!(found, RoomPage::default());
+            assert_eq!(all, RoomPage::default());
+            assert_eq!(hotel.get_available_rooms_page(1, 1).next, Some(2));
+        }
 
-    #[ink(event)]
-    pub struct AgreementTerminatedEvent {
-        #[ink(topic)]
-        room_id: RoomId,
-    }
+        #[ink::test]
+        fn room_queries_are_paged() {
+            // given more rooms than fit a page, every other room is a double
+            let (mut hotel, _) = hotel_with_room();
+            let double = RoomAttributes {
+                category: RoomCategory::Double,
+                max_occupants: 2,
+                ..Default::default()
+            };
+            for _ in 0..MAX_PAGE_SIZE {
+                add_rooms(&mut hotel, 1, double.clone());
+                add_rooms(&mut hotel, 1, Default::default());
+            }
 
-    impl Hotel {
-        #[ink(constructor, payable)]
-        pub fn new() -> Self {
-            let mut instance = Self::default();
-            instance._init_with_owner(Self::env().caller());
-            instance.hotel_data.land_lord = Self::env().caller();
-            instance
+            // when all the rooms are asked for at once
+            let page = hotel.get_rooms_page(0, u32::MAX).unwrap();
+
+            // then a page is capped at `MAX_PAGE_SIZE` rooms
+            assert_eq!(page.rooms.len(), MAX_PAGE_SIZE as usize);
+            assert_eq!(page.next, Some(MAX_PAGE_SIZE as RoomId));
+            let rooms = hotel.get_room().unwrap();
+            assert_eq!(rooms.len(), MAX_PAGE_SIZE as usize);
+            assert_eq!(rooms[0], page.rooms[0].room);
+            assert_eq!(
+                hotel.get_available_rooms_page(0, u32::MAX).rooms.len(),
+                MAX_PAGE_SIZE as usize
+            );
+
+            // and search pages through the matching rooms only
+            let filter = RoomFilter {
+                category: Some(RoomCategory::Double),
+                ..Default::default()
+            };
+            let first = hotel.search_rooms(filter.clone(), 0, 2);
+            let room_ids: Vec<RoomId> = first
+                .rooms
+                .iter()
+                .map(|listing| listing.room.room_id)
+                .collect();
+            assert_eq!(room_ids, vec![1, 3]);
+            assert_eq!(first.next, Some(4));
+            let capped = hotel.search_rooms(filter.clone(), 0, u32::MAX);
+            assert_eq!(capped.rooms.len(), MAX_PAGE_SIZE as usize);
+            assert_eq!(capped.next, Some(2 * MAX_PAGE_SIZE as RoomId));
+
+            // and only `ACCOUNTANT` pages through every room
+            set_caller(accounts().bob);
+            assert_eq!(hotel.get_room(), Err(HotelError::MissingAccountantRole));
+            assert_eq!(
+                hotel.get_rooms_page(0, 10),
+                Err(HotelError::MissingAccountantRole)
+            );
         }
-    }
 
-    impl HotelRoomBookingEvents for Hotel {
-        fn emit_add_room_event(&self, room_id: RoomId, owner: AccountId) {
-            self.env().emit_event(AddRoomEvent { room_id, owner });
+        #[ink::test]
+        fn search_scans_a_bounded_number_of_rooms() {
+            // given more rooms than a call scans, none of them a suite
+            let (mut hotel, _) = hotel_with_room();
+            add_rooms(&mut hotel, MAX_PAGE_SCAN, Default::default());
+            let filter = RoomFilter {
+                category: Some(RoomCategory::Suite),
+                ..Default::default()
+            };
+
+            // when
+            let page = hotel.search_rooms(filter.clone(), 0, 10);
+
+            // then the search stops after `MAX_PAGE_SCAN` ids and resumes from there
+            assert!(page.rooms.is_empty());
+            assert_eq!(page.next, Some(MAX_PAGE_SCAN as RoomId));
+            assert_eq!(
+                hotel.search_rooms(filter, MAX_PAGE_SCAN as RoomId, 10),
+                RoomPage::default()
+            );
         }
-        fn emit_sign_agreement_event(&self, room_id: RoomId, agreement_signer: AccountId) {
-            self.env().emit_event(SignAgreementEvent {
-                room_id,
-                agreement_signer,
-            });
+
+        #[ink::test]
+        fn rooms_of_landlords_and_tenants_are_paged() {
+            // given three rooms of eve, all of them rented by bob
+            let (mut hotel, hotel_room_id) = hotel_with_room();
+            let accounts = accounts();
+            hotel
+                .register_landlord(accounts.eve)
+                .expect("register_landlord failed");
+            set_caller(accounts.eve);
+            let mut room_ids: Vec<RoomId> = Vec::new();
+            for _ in 0..3 {
+                let room_id = hotel
+                    .add_room(
+                        String::from("room name"),
+                        String::from("room address"),
+                        RENT,
+                        DEPOSIT,
+                        0,
+                        Default::default(),
+                    )
+                    .expect("add_room failed");
+                room_ids.push(room_id);
+                pay(accounts.bob, RENT + DEPOSIT);
+                hotel
+                    .sign_agreement(room_id)
+                    .expect("sign_agreement failed");
+                set_caller(accounts.eve);
+            }
+            assert_eq!(hotel.tenant_room_count(accounts.bob), 3);
+
+            // when bob leaves the first room, the last one takes its position
+            set_caller(accounts.bob);
+            hotel
+                .exit_agreement(room_ids[0])
+                .expect("exit_agreement failed");
+
+            // then the rooms of bob are paged by position
+            let rented = |hotel: &Hotel, start, limit| -> Vec<RoomId> {
+                hotel
+                    .get_rooms_by_tenant(accounts.bob, start, limit)
+                    .iter()
+                    .map(|listing| listing.room.room_id)
+                    .collect()
+            };
+            assert_eq!(hotel.tenant_room_count(accounts.bob), 2);
+            assert_eq!(rented(&hotel, 0, u32::MAX), vec![room_ids[2], room_ids[1]]);
+            assert_eq!(rented(&hotel, 1, 1), vec![room_ids[1]]);
+            assert!(rented(&hotel, 2, 1).is_empty());
+
+            // when eve takes the hotel over
+            set_caller(accounts.alice);
+            hotel
+                .propose_new_landlord(accounts.eve)
+                .expect("propose_new_landlord failed");
+            set_caller(accounts.eve);
+            hotel
+                .accept_landlordship()
+                .expect("accept_landlordship failed");
+
+            // then the own rooms of eve are followed by the rooms of the hotel
+            let owned = |hotel: &Hotel, start, limit| -> Vec<RoomId> {
+                hotel
+                    .get_rooms_by_landlord(accounts.eve, start, limit)
+                    .iter()
+                    .map(|listing| listing.room.room_id)
+                    .collect()
+            };
+            assert_eq!(hotel.landlord_room_count(accounts.eve), 4);
+            assert_eq!(hotel.landlord_room_count(accounts.alice), 0);
+            assert_eq!(
+                owned(&hotel, 0, u32::MAX),
+                vec![room_ids[0], room_ids[1], room_ids[2], hotel_room_id]
+            );
+            assert_eq!(owned(&hotel, 2, 2), vec![room_ids[2], hotel_room_id]);
+            assert!(owned(&hotel, 0, 0).is_empty());
         }
-        fn emit_rent_payment_event(&self, room_id: RoomId, rent_payment_signer: AccountId) {
-            self.env().emit_event(RentPaymentEvent {
-                room_id,
-                rent_payment_signer,
-            });
+
+        #[ink::test]
+        fn available_rooms_follow_the_vacancy_index() {
+            // given four rooms and bob renting the first one, which moves the last room
+            // to its position in the vacancy index
+            let (mut hotel, room_id) = hotel_with_room();
+            let accounts = accounts();
+            add_rooms(&mut hotel, 3, Default::default());
+            pay(accounts.bob, RENT + DEPOSIT);
+            hotel
+                .sign_agreement(room_id)
+                .expect("sign_agreement failed");
+
+            // when
+            let rooms = hotel.get_available_room();
+            let first = hotel.get_available_rooms_page(0, 2);
+            let second = hotel.get_available_rooms_page(first.next.unwrap(), 2);
+
+            // then the unpaged query follows the vacancy index and pages follow the room ids
+            let room_ids: Vec<RoomId> = rooms.iter().map(|room| room.room_id).collect();
+            assert_eq!(room_ids, vec![3, 1, 2]);
+            let paged: Vec<RoomId> = first
+                .rooms
+                .iter()
+                .chain(second.rooms.iter())
+                .map(|listing| listing.room.room_id)
+                .collect();
+            assert_eq!(paged, vec![1, 2, 3]);
+            assert_eq!(second.next, None);
+
+            // and the unpaged query is capped
+            add_rooms(&mut hotel, MAX_PAGE_SIZE, Default::default());
+            assert_eq!(hotel.get_available_room().len(), MAX_PAGE_SIZE as usize);
         }
-        fn emit_agreement_complete_event(&self, room_id: RoomId) {
-            self.env().emit_event(AgreementCompletedEvent { room_id });
+
+        #[ink::test]
+        fn available_room_returns_the_first_positions_of_the_vacancy_index() {
+            // given more available rooms than fit a page and bob renting the first one,
+            // the last room takes its position in the vacancy index
+            let (mut hotel, room_id) = hotel_with_room();
+            let accounts = accounts();
+            add_rooms(&mut hotel, MAX_PAGE_SIZE + 9, Default::default());
+            let last_room_id = MAX_PAGE_SIZE as RoomId + 9;
+            pay(accounts.bob, RENT + DEPOSIT);
+            hotel
+                .sign_agreement(room_id)
+                .expect("sign_agreement failed");
+
+            // when
+            let rooms = hotel.get_available_room();
+
+            // then exactly the rooms of the first `MAX_PAGE_SIZE` positions come back
+            let room_ids: Vec<RoomId> = rooms.iter().map(|room| room.room_id).collect();
+            let expected: Vec<RoomId> = [last_room_id]
+                .into_iter()
+                .chain(1..MAX_PAGE_SIZE as RoomId)
+                .collect();
+            assert_eq!(room_ids, expected);
         }
-        fn emit_agreement_terminated_event(&self, room_id: RoomId) {
-            self.env().emit_event(AgreementTerminatedEvent { room_id });
+
+        #[ink::test]
+        fn malformed_cids_are_rejected() {
+            // given
+            let (mut hotel, room_id) = hotel_with_room();
+            let cid = "bafybeigdyrzt5sfp7udm7hu76uh7y26nf3efuylqabf3oclgtqy55fbzdi";
+            let malformed = [
+                // truncated digest and trailing character
+                &cid[..cid.len() - 1],
+                "bafybeigdyrzt5sfp7udm7hu76uh7y26nf3efuylqabf3oclgtqy55fbzdia",
+                // uppercase, characters outside of base32 and unused padding bits
+                "BAFYBEIGDYRZT5SFP7UDM7HU76UH7Y26NF3EFUYLQABF3OCLGTQY55FBZDI",
+                "bafybeigdyrzt5sfp7udm7hu76uh7y26nf3efuylqabf3oclgtqy55fbzd1",
+                "bafybeigdyrzt5sfp7udm7hu76uh7y26nf3efuylqabf3oclgtqy55fbzdj",
+                // version 0 in base32, an empty digest and no content at all
+                "babybeigdyrzt5sfp7udm7hu76uh7y26nf3efuylqabf3oclgtqy55fbzdi",
+                "bafybeaa",
+                "b",
+                // not minimally encoded varint
+                "bahyaaeraaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaa",
+            ];
+
+            // when
+            for content in malformed {
+                assert_eq!(
+                    hotel.set_room_metadata(
+                        room_id,
+                        ContentId::Cid(String::from(content)),
+                        String::from("image/png"),
+                    ),
+                    Err(HotelError::InvalidContentId)
+                );
+            }
+            assert_eq!(
+                hotel.set_room_metadata(
+                    room_id,
+                    ContentId::Cid("b".repeat(MAX_CID_LENGTH + 1)),
+                    String::from("image/png"),
+                ),
+                Err(HotelError::InvalidContentId)
+            );
+            assert_eq!(hotel.get_room_metadata(room_id), None);
+
+            // then well formed CIDs are stored and listed with the room
+            let raw_cid = "bafkreigh2akiscaildcqabsyg3dfr6chu3fgpregiymsck7e7aqa4s52zy";
+            for content in [cid, raw_cid] {
+                hotel
+                    .set_room_metadata(
+                        room_id,
+                        ContentId::Cid(String::from(content)),
+                        String::from("image/png"),
+                    )
+                    .expect("set_room_metadata failed");
+            }
+            let rooms = hotel.get_rooms_by_landlord(accounts().alice, 0, MAX_PAGE_SIZE);
+            let metadata = rooms[0].metadata.clone().expect("metadata not listed");
+            assert_eq!(metadata.content, ContentId::Cid(String::from(raw_cid)));
         }
     }
 
     #[cfg(all(test, feature = "e2e-tests"))]
     mod e2e_tests {
-        use crate::contract::types::Room;
+        use crate::contract::types::{
+            ContentId, HotelError, Room, RoomAttributes, RoomCategory, RoomFilter,
+        };
         use ink_e2e::build_message;
+        use legacy_hotel::legacy_hotel::LegacyHotelRef;
         use logics::traits::room_book::roombook_external::RoomBook;
         use openbrush::traits::ZERO_ADDRESS;
+        use reentrant_token::reentrant_token::ReentrantTokenRef;
 
         use super::*;
         type E2EResult<T> = std::result::Result<T, Box<dyn std::error::Error>>;
@@ -127,6 +2530,156 @@ mod contract {
             Ok(())
         }
 
+        #[ink_e2e::test(additional_contracts = "legacy_hotel/Cargo.toml")]
+        async fn upgrade_converts_rooms_of_version_1(
+            mut client: ink_e2e::Client<C, E>,
+        ) -> E2EResult<()> {
+            // given a hotel that ran a build storing two rooms with the layout of version 1
+            let constructor = HotelRef::new();
+            let contract_acc_id = client
+                .instantiate("contract", &ink_e2e::alice(), constructor, 1000, None)
+                .await
+                .expect("failed to instantiate")
+                .account_id;
+
+            let legacy_upload = client
+                .upload("legacy_hotel", &ink_e2e::alice(), None)
+                .await
+                .expect("failed to upload legacy_hotel");
+            let mut legacy_code_hash = [0u8; 32];
+            legacy_code_hash.copy_from_slice(legacy_upload.code_hash.as_ref());
+            let upgrade = build_message::<HotelRef>(contract_acc_id.clone())
+                .call(|hotel| hotel.upgrade(legacy_code_hash));
+            client
+                .call(&ink_e2e::alice(), upgrade, 0, None)
+                .await
+                .expect("calling upgrade failed");
+
+            let seed_rooms = build_message::<LegacyHotelRef>(contract_acc_id.clone())
+                .call(|hotel| hotel.seed_rooms(2, 10));
+            client
+                .call(&ink_e2e::alice(), seed_rooms, 0, None)
+                .await
+                .expect("calling seed_rooms failed");
+
+            // when the hotel gets the current code back and is migrated
+            let upload = client
+                .upload("contract", &ink_e2e::alice(), None)
+                .await
+                .expect("failed to upload");
+            let mut code_hash = [0u8; 32];
+            code_hash.copy_from_slice(upload.code_hash.as_ref());
+            let upgrade = build_message::<LegacyHotelRef>(contract_acc_id.clone())
+                .call(|hotel| hotel.upgrade(code_hash));
+            client
+                .call(&ink_e2e::alice(), upgrade, 0, None)
+                .await
+                .expect("calling upgrade of legacy_hotel failed");
+
+            let get_storage_version = build_message::<HotelRef>(contract_acc_id.clone())
+                .call(|hotel| hotel.get_storage_version());
+            let get_storage_version_res = client
+                .call_dry_run(&ink_e2e::alice(), &get_storage_version, 0, None)
+                .await;
+            assert_eq!(get_storage_version_res.return_value(), 1);
+
+            let migrate = build_message::<HotelRef>(contract_acc_id.clone())
+                .call(|hotel| hotel.migrate(0, 10));
+            let migrate_res = client
+                .call(&ink_e2e::alice(), migrate, 0, None)
+                .await
+                .expect("calling migrate failed");
+            assert_eq!(migrate_res.return_value(), Ok(None));
+
+            // then the rooms are read with the current layout
+            let get_storage_version_res = client
+                .call_dry_run(&ink_e2e::alice(), &get_storage_version, 0, None)
+                .await;
+            assert_eq!(
+                get_storage_version_res.return_value(),
+                types::STORAGE_VERSION
+            );
+
+            let get_available_room = build_message::<HotelRef>(contract_acc_id.clone())
+                .call(|hotel| hotel.get_available_room());
+            let get_available_room_res = client
+                .call_dry_run(&ink_e2e::alice(), &get_available_room, 0, None)
+                .await;
+            let rooms = get_available_room_res.return_value();
+            assert_eq!(rooms.len(), 2);
+            let alice = ink_e2e::account_id(ink_e2e::AccountKeyring::Alice);
+            for room in rooms {
+                assert!(room.listed);
+                assert_eq!(room.rent_per_month, 10);
+                assert_eq!(room.landlord, alice);
+            }
+
+            let migrate = build_message::<HotelRef>(contract_acc_id.clone())
+                .call(|hotel| hotel.migrate(0, 10));
+            let migrate_res = client
+                .call_dry_run(&ink_e2e::alice(), &migrate, 0, None)
+                .await;
+            assert_eq!(migrate_res.return_value(), Err(HotelError::AlreadyMigrated));
+
+            Ok(())
+        }
+
+        #[ink_e2e::test]
+        async fn landlordship_transfer_works(mut client: ink_e2e::Client<C, E>) -> E2EResult<()> {
+            // given
+            let bob = ink_e2e::account_id(ink_e2e::AccountKeyring::Bob);
+
+            let constructor = HotelRef::new();
+            let contract_acc_id = client
+                .instantiate("contract", &ink_e2e::alice(), constructor, 1000, None)
+                .await
+                .expect("failed to instantiate")
+                .account_id;
+
+            // when
+            let propose_new_landlord = build_message::<HotelRef>(contract_acc_id.clone())
+                .call(|hotel| hotel.propose_new_landlord(bob));
+            client
+                .call(&ink_e2e::alice(), propose_new_landlord, 0, None)
+                .await
+                .expect("calling propose_new_landlord failed");
+
+            // alice stays the landlord until bob accepts
+            let get_landlord = build_message::<HotelRef>(contract_acc_id.clone())
+                .call(|hotel| hotel.get_landlord());
+            let get_landlord_res = client
+                .call_dry_run(&ink_e2e::alice(), &get_landlord, 0, None)
+                .await;
+            assert_eq!(
+                get_landlord_res.return_value(),
+                ink_e2e::account_id(ink_e2e::AccountKeyring::Alice)
+            );
+
+            let accept_landlordship = build_message::<HotelRef>(contract_acc_id.clone())
+                .call(|hotel| hotel.accept_landlordship());
+            client
+                .call(&ink_e2e::bob(), accept_landlordship, 0, None)
+                .await
+                .expect("calling accept_landlordship failed");
+
+            // then
+            let get_landlord = build_message::<HotelRef>(contract_acc_id.clone())
+                .call(|hotel| hotel.get_landlord());
+            let get_landlord_res = client
+                .call_dry_run(&ink_e2e::alice(), &get_landlord, 0, None)
+                .await;
+            assert_eq!(get_landlord_res.return_value(), bob);
+
+            let get_pending_landlord = build_message::<HotelRef>(contract_acc_id.clone())
+                .call(|hotel| hotel.get_pending_landlord());
+            let get_pending_landlord_res = client
+                .call_dry_run(&ink_e2e::alice(), &get_pending_landlord, 0, None)
+                .await;
+            assert_eq!(get_pending_landlord_res.return_value(), None);
+
+            Ok(())
+        }
+
         #[ink_e2e::test]
         async fn add_room_works(mut client: ink_e2e::Client<C, E>) -> E2EResult<()> {
             // given
@@ -144,6 +2697,13 @@ mod contract {
             let rent_per_month = 10;
             let security_deposit = 10;
             let time_stamp = 10;
+            let attributes = RoomAttributes {
+                category: RoomCategory::Double,
+                max_occupants: 2,
+                beds: 1,
+                floor_area: 20,
+                amenities: types::AMENITY_BALCONY | types::AMENITY_WIFI,
+            };
 
             // Add room
             let add_room = build_message::<HotelRef>(contract_acc_id.clone()).call(|hotel| {
@@ -153,6 +2713,7 @@ mod contract {
                     rent_per_month,
                     security_deposit,
                     time_stamp,
+                    attributes.clone(),
                 )
             });
 
@@ -189,7 +2750,7 @@ mod contract {
 
             // get the room
             let get_room =
-                build_message::<HotelRef>(contract_acc_id.clone()).call(|hote| hote.get_room());
+                build_message::<HotelRef>(contract_acc_id.clone()).call(|hotel| hotel.get_room());
 
             let get_room_result = client
                 .call_dry_run(&ink_e2e::alice(), &get_room, 0, None)
@@ -210,12 +2771,193 @@ mod contract {
                     landlord: alice,
                     current_tenant: ZERO_ADDRESS.into(),
                     next_rent_due_date: 0,
+                    billing_period: types::DEFAULT_BILLING_PERIOD,
+                    late_fee_policy: Default::default(),
+                    lease_terms: Default::default(),
+                    listed: true,
+                    attributes,
                 }]
             );
 
             Ok(())
         }
 
+        #[ink_e2e::test]
+        async fn search_rooms_works(mut client: ink_e2e::Client<C, E>) -> E2EResult<()> {
+            // given
+            let constructor = HotelRef::new();
+            let contract_acc_id = client
+                .instantiate("contract", &ink_e2e::alice(), constructor, 1000, None)
+                .await
+                .expect("failed to instantiate")
+                .account_id;
+
+            // Add a double room with balcony and a single room
+            let add_double_room =
+                build_message::<HotelRef>(contract_acc_id.clone()).call(|hotel| {
+                    hotel.add_room(
+                        String::from("double room"),
+                        String::from("room address"),
+                        450,
+                        10,
+                        10,
+                        RoomAttributes {
+                            category: RoomCategory::Double,
+                            max_occupants: 2,
+                            beds: 1,
+                            floor_area: 20,
+                            amenities: types::AMENITY_BALCONY | types::AMENITY_WIFI,
+                        },
+                    )
+                });
+            client
+                .call(&ink_e2e::alice(), add_double_room, 0, None)
+                .await
+                .expect("calling add_room failed");
+
+            let add_single_room =
+                build_message::<HotelRef>(contract_acc_id.clone()).call(|hotel| {
+                    hotel.add_room(
+                        String::from("single room"),
+                        String::from("room address"),
+                        300,
+                        10,
+                        10,
+                        RoomAttributes::default(),
+                    )
+                });
+            client
+                .call(&ink_e2e::alice(), add_single_room, 0, None)
+                .await
+                .expect("calling add_room failed");
+
+            // when
+            let search_rooms = build_message::<HotelRef>(contract_acc_id.clone()).call(|hotel| {
+                hotel.search_rooms(
+                    RoomFilter {
+                        category: Some(RoomCategory::Double),
+                        max_rent: Some(500),
+                        amenities: types::AMENITY_BALCONY,
+                        ..Default::default()
+                    },
+                    0,
+                    10,
+                )
+            });
+            let search_rooms_res = client
+                .call_dry_run(&ink_e2e::alice(), &search_rooms, 0, None)
+                .await;
+
+            // then only the double room matches
+            let page = search_rooms_res.return_value();
+            assert_eq!(page.rooms.len(), 1);
+            assert_eq!(page.rooms[0].room.room_id, 0);
+            assert_eq!(page.next, None);
+
+            // and both rooms can be paged one at a time
+            let get_available_rooms_page = build_message::<HotelRef>(contract_acc_id.clone())
+                .call(|hotel| hotel.get_available_rooms_page(0, 1));
+            let first_page = client
+                .call_dry_run(&ink_e2e::alice(), &get_available_rooms_page, 0, None)
+                .await
+                .return_value();
+            assert_eq!(first_page.rooms.len(), 1);
+            assert_eq!(first_page.next, Some(1));
+
+            let get_available_rooms_page = build_message::<HotelRef>(contract_acc_id.clone())
+                .call(|hotel| hotel.get_available_rooms_page(1, 1));
+            let second_page = client
+                .call_dry_run(&ink_e2e::alice(), &get_available_rooms_page, 0, None)
+                .await
+                .return_value();
+            assert_eq!(second_page.rooms[0].room.room_id, 1);
+            assert_eq!(second_page.next, None);
+
+            let available_room_count = build_message::<HotelRef>(contract_acc_id.clone())
+                .call(|hotel| hotel.available_room_count());
+            let available_room_count_res = client
+                .call_dry_run(&ink_e2e::alice(), &available_room_count, 0, None)
+                .await;
+            assert_eq!(available_room_count_res.return_value(), 2);
+
+            Ok(())
+        }
+
+        #[ink_e2e::test]
+        async fn room_metadata_works(mut client: ink_e2e::Client<C, E>) -> E2EResult<()> {
+            // given
+            let constructor = HotelRef::new();
+            let contract_acc_id = client
+                .instantiate("contract", &ink_e2e::alice(), constructor, 1000, None)
+                .await
+                .expect("failed to instantiate")
+                .account_id;
+
+            let add_room = build_message::<HotelRef>(contract_acc_id.clone()).call(|hotel| {
+                hotel.add_room(
+                    String::from("room name"),
+                    String::from("room address"),
+                    10,
+                    10,
+                    10,
+                    RoomAttributes::default(),
+                )
+            });
+            client
+                .call(&ink_e2e::alice(), add_room, 0, None)
+                .await
+                .expect("calling add_room failed");
+
+            let content = ContentId::Cid(String::from(
+                "QmYwAPJzv5CZsnA625s3Xf2nemtYgPpHdWEz79ojWnPbdG",
+            ));
+
+            // when a media type with parameters is given
+            let set_room_metadata =
+                build_message::<HotelRef>(contract_acc_id.clone()).call(|hotel| {
+                    hotel.set_room_metadata(
+                        0,
+                        content.clone(),
+                        String::from("text/html; charset=utf-8"),
+                    )
+                });
+            let set_room_metadata_res = client
+                .call_dry_run(&ink_e2e::alice(), &set_room_metadata, 0, None)
+                .await;
+
+            // then it is rejected
+            assert_eq!(
+                set_room_metadata_res.return_value(),
+                Err(HotelError::InvalidMimeType)
+            );
+
+            // when a valid CID is given
+            let set_room_metadata =
+                build_message::<HotelRef>(contract_acc_id.clone()).call(|hotel| {
+                    hotel.set_room_metadata(0, content.clone(), String::from("image/png"))
+                });
+            client
+                .call(&ink_e2e::alice(), set_room_metadata, 0, None)
+                .await
+                .expect("calling set_room_metadata failed");
+
+            // then it is listed with the room
+            let get_available_rooms_page = build_message::<HotelRef>(contract_acc_id.clone())
+                .call(|hotel| hotel.get_available_rooms_page(0, 10));
+            let rooms = client
+                .call_dry_run(&ink_e2e::alice(), &get_available_rooms_page, 0, None)
+                .await
+                .return_value()
+                .rooms;
+            assert_eq!(rooms.len(), 1);
+
+            let metadata = rooms[0].metadata.clone().expect("metadata not listed");
+            assert_eq!(metadata.content, content);
+            assert_eq!(metadata.mime_type, String::from("image/png"));
+
+            Ok(())
+        }
+
         #[ink_e2e::test]
         async fn sign_agreement_works(mut client: ink_e2e::Client<C, E>) -> E2EResult<()> {
             // given
@@ -231,6 +2973,13 @@ mod contract {
             let rent_per_month = 10;
             let security_deposit = 10;
             let time_stamp = 10;
+            let attributes = RoomAttributes {
+                category: RoomCategory::Double,
+                max_occupants: 2,
+                beds: 1,
+                floor_area: 20,
+                amenities: types::AMENITY_BALCONY | types::AMENITY_WIFI,
+            };
 
             // Add room
             let room_id = build_message::<HotelRef>(contract_acc_id.clone()).call(|hotel| {
@@ -240,6 +2989,7 @@ mod contract {
                     rent_per_month,
                     security_deposit,
                     time_stamp,
+                    attributes.clone(),
                 )
             });
 
@@ -248,6 +2998,20 @@ mod contract {
                 .await
                 .expect("calling add_room failed");
 
+            // Verify bob as a tenant
+            let attest_tenant = build_message::<HotelRef>(contract_acc_id.clone()).call(|hotel| {
+                hotel.attest_tenant(
+                    ink_e2e::account_id(ink_e2e::AccountKeyring::Bob),
+                    VerificationStatus::Verified,
+                    u64::MAX,
+                    [1; 32],
+                )
+            });
+            client
+                .call(&ink_e2e::alice(), attest_tenant, 0, None)
+                .await
+                .expect("calling attest_tenant failed");
+
             // Sign Agreement
             let sign_agreement = build_message::<HotelRef>(contract_acc_id.clone())
                 .call(|hotel| hotel.sign_agreement(0));
@@ -259,11 +3023,11 @@ mod contract {
                 .await
                 .expect("calling sign agreement failed");
 
-            // check event message for sign agreement
+            // check event message for sign agreement, it is emitted after the claim credit
             let contract_emitted_event = sign_agreement_response
                 .events
                 .iter()
-                .find(|event| {
+                .filter(|event| {
                     event
                         .as_ref()
                         .expect("Expect Event")
@@ -271,6 +3035,7 @@ mod contract {
                         .event()
                         == "ContractEmitted"
                 })
+                .last()
                 .expect("Expect ContractEmitted event")
                 .unwrap();
 
@@ -279,6 +3044,177 @@ mod contract {
             let decoded_event = <SignAgreementEvent as scale::Decode>::decode(&mut &event[34..])
                 .expect("Invalid data");
 
+            let SignAgreementEvent {
+                room_id,
+                agreement_signer,
+                credited,
+            } = decoded_event;
+
+            // assert with expected value
+            assert_eq!(room_id, 0);
+            assert_eq!(
+                agreement_signer,
+                ink_e2e::account_id(ink_e2e::AccountKeyring::Bob)
+            );
+            assert_eq!(credited, 100 - rent_per_month - security_deposit);
+
+            // check agreement is stored for the room
+            let get_agreements = build_message::<HotelRef>(contract_acc_id.clone())
+                .call(|hotel| hotel.get_agreements_for_room(0));
+            let get_agreements_result = client
+                .call_dry_run(&ink_e2e::alice(), &get_agreements, 0, None)
+                .await;
+
+            let agreements = get_agreements_result.return_value();
+            assert_eq!(agreements.len(), 1);
+            assert_eq!(
+                agreements[0].tenant,
+                ink_e2e::account_id(ink_e2e::AccountKeyring::Bob)
+            );
+            assert_eq!(agreements[0].rent_per_month, rent_per_month);
+            assert_eq!(agreements[0].security_deposit, security_deposit);
+
+            // check the room is indexed for bob
+            let get_rooms_by_tenant =
+                build_message::<HotelRef>(contract_acc_id.clone()).call(|hotel| {
+                    hotel.get_rooms_by_tenant(
+                        ink_e2e::account_id(ink_e2e::AccountKeyring::Bob),
+                        0,
+                        10,
+                    )
+                });
+            let get_rooms_by_tenant_result = client
+                .call_dry_run(&ink_e2e::alice(), &get_rooms_by_tenant, 0, None)
+                .await;
+            let rooms = get_rooms_by_tenant_result.return_value();
+            assert_eq!(rooms.len(), 1);
+            assert_eq!(rooms[0].room.room_id, 0);
+
+            // and is no longer available
+            let get_available_room = build_message::<HotelRef>(contract_acc_id.clone())
+                .call(|hotel| hotel.get_available_room());
+            let get_available_room_res = client
+                .call_dry_run(&ink_e2e::alice(), &get_available_room, 0, None)
+                .await;
+            assert!(get_available_room_res.return_value().is_empty());
+
+            Ok(())
+        }
+
+        #[ink_e2e::test(additional_contracts = "reentrant_token/Cargo.toml")]
+        async fn reentrant_token_call_is_rejected(
+            mut client: ink_e2e::Client<C, E>,
+        ) -> E2EResult<()> {
+            // given
+            let constructor = HotelRef::new();
+            let contract_acc_id = client
+                .instantiate("contract", &ink_e2e::alice(), constructor, 1000, None)
+                .await
+                .expect("failed to instantiate")
+                .account_id;
+
+            // token that withdraws a claim of 10 from `transfer_from`
+            let token_constructor = ReentrantTokenRef::new(contract_acc_id.clone(), 10);
+            let token_acc_id = client
+                .instantiate(
+                    "reentrant_token",
+                    &ink_e2e::alice(),
+                    token_constructor,
+                    0,
+                    None,
+                )
+                .await
+                .expect("failed to instantiate token")
+                .account_id;
+
+            // the token is a landlord with room 0
+            let register_landlord = build_message::<HotelRef>(contract_acc_id.clone())
+                .call(|hotel| hotel.register_landlord(token_acc_id.clone()));
+            client
+                .call(&ink_e2e::alice(), register_landlord, 0, None)
+                .await
+                .expect("calling register_landlord failed");
+            let token_add_room = build_message::<ReentrantTokenRef>(token_acc_id.clone())
+                .call(|token| token.add_room(10, 10));
+            client
+                .call(&ink_e2e::alice(), token_add_room, 0, None)
+                .await
+                .expect("calling add_room of the token failed");
+
+            // Verify bob and charlie as tenants
+            for account in [
+                ink_e2e::AccountKeyring::Bob,
+                ink_e2e::AccountKeyring::Charlie,
+            ] {
+                let attest_tenant =
+                    build_message::<HotelRef>(contract_acc_id.clone()).call(|hotel| {
+                        hotel.attest_tenant(
+                            ink_e2e::account_id(account),
+                            VerificationStatus::Verified,
+                            u64::MAX,
+                            [1; 32],
+                        )
+                    });
+                client
+                    .call(&ink_e2e::alice(), attest_tenant, 0, None)
+                    .await
+                    .expect("calling attest_tenant failed");
+            }
+
+            // bob rents room 0, the token can withdraw the rent of 10
+            let sign_agreement = build_message::<HotelRef>(contract_acc_id.clone())
+                .call(|hotel| hotel.sign_agreement(0));
+            client
+                .call(&ink_e2e::bob(), sign_agreement, 20, None)
+                .await
+                .expect("calling sign_agreement failed");
+
+            // room 1 of the hotel is paid in the token
+            let add_room = build_message::<HotelRef>(contract_acc_id.clone()).call(|hotel| {
+                hotel.add_room(
+                    String::from("room one"),
+                    String::from("room address"),
+                    10,
+                    10,
+                    10,
+                    RoomAttributes::default(),
+                )
+            });
+            client
+                .call(&ink_e2e::alice(), add_room, 0, None)
+                .await
+                .expect("calling add_room failed");
+            let set_accepted_token = build_message::<HotelRef>(contract_acc_id.clone())
+                .call(|hotel| hotel.set_accepted_token(Some(token_acc_id.clone())));
+            client
+                .call(&ink_e2e::alice(), set_accepted_token, 0, None)
+                .await
+                .expect("calling set_accepted_token failed");
+
+            // when the hotel pulls the token, which calls `withdraw` on the hotel
+            let sign_agreement_with_token = build_message::<HotelRef>(contract_acc_id.clone())
+                .call(|hotel| hotel.sign_agreement_with_token(1));
+            client
+                .call(&ink_e2e::charlie(), sign_agreement_with_token, 0, None)
+                .await
+                .expect("calling sign_agreement_with_token failed");
+
+            // then the runtime denied the nested `withdraw` before it reached the hotel
+            let reentry_result = build_message::<ReentrantTokenRef>(token_acc_id.clone())
+                .call(|token| token.reentry_result());
+            let reentry_result_res = client
+                .call_dry_run(&ink_e2e::alice(), &reentry_result, 0, None)
+                .await;
+            assert_eq!(reentry_result_res.return_value(), None);
+
+            // and the claim of the token is untouched
+            let balance_of_claims = build_message::<HotelRef>(contract_acc_id.clone())
+                .call(|hotel| hotel.balance_of_claims(token_acc_id.clone()));
+            let balance_of_claims_res = client
+                .call_dry_run(&ink_e2e::alice(), &balance_of_claims, 0, None)
+                .await;
+            assert_eq!(balance_of_claims_res.return_value(), 10);
+
             Ok(())
         }
     }
@@ -0,0 +1,216 @@
+use crate::impls::room_book::types::{
+    AgreementId, Currency, LateFeePolicy, LeaseTerms, Proposal, ProposalAction, ProposalId,
+    ProposalStatus, Room, RoomAgreement, RoomId,
+};
+use ink::{
+    prelude::{string::String, vec::Vec},
+    primitives::Key,
+};
+use openbrush::traits::{AccountId, Balance, Timestamp};
+
+// layouts of `Room`, `RoomAgreement` and `Proposal` stored by storage version 1, records
+// are converted to the current layout by `migrate`, upgrades start at version 1 since
+// earlier builds couldn't upgrade
+
+#[derive(scale::Decode, scale::Encode, Debug, Clone, Eq, PartialEq)]
+pub struct RoomV1 {
+    pub room_id: RoomId,
+    pub agreement_id: AgreementId,
+    pub room_name: String,
+    pub room_address: String,
+    pub rent_per_month: u128,
+    pub security_deposit: u128,
+    pub time_stamp: Timestamp,
+    pub vacant: bool,
+    pub landlord: AccountId,
+    pub current_tenant: AccountId,
+    pub next_rent_due_date: Timestamp,
+    pub billing_period: Timestamp,
+    pub late_fee_policy: LateFeePolicy,
+    pub lease_terms: LeaseTerms,
+}
+
+#[derive(scale::Decode, scale::Encode, Debug, Clone, Eq, PartialEq)]
+pub struct RoomAgreementV1 {
+    pub room_id: RoomId,
+    pub agreement_id: AgreementId,
+    pub room_name: String,
+    pub room_address: String,
+    pub rent_per_month: u128,
+    pub security_deposit: u128,
+    pub lock_in_period: Timestamp,
+    pub time_stamp: Timestamp,
+    pub start_time: Timestamp,
+    pub end_time: Timestamp,
+    pub early_exit_penalty: u128,
+    pub tenant: AccountId,
+    pub landlord: AccountId,
+    pub currency: Currency,
+}
+
+#[derive(scale::Decode, scale::Encode, Debug, Clone, Eq, PartialEq)]
+pub enum ProposalActionV1 {
+    TerminateAgreement {
+        room_id: RoomId,
+    },
+    ForfeitDeposit {
+        room_id: RoomId,
+        amount: Balance,
+    },
+    ChangeRent {
+        room_id: RoomId,
+        rent_per_month: Balance,
+    },
+}
+
+#[derive(scale::Decode, scale::Encode, Debug, Clone, Eq, PartialEq)]
+pub struct ProposalV1 {
+    pub proposal_id: ProposalId,
+    pub action: ProposalActionV1,
+    pub proposer: AccountId,
+    pub approvals: Vec<AccountId>,
+    pub deadline: Timestamp,
+    pub status: ProposalStatus,
+    pub time_stamp: Timestamp,
+}
+
+impl ProposalActionV1 {
+    pub fn room_id(&self) -> RoomId {
+        match *self {
+            ProposalActionV1::TerminateAgreement { room_id }
+            | ProposalActionV1::ForfeitDeposit { room_id, .. }
+            | ProposalActionV1::ChangeRent { room_id, .. } => room_id,
+        }
+    }
+}
+
+impl RoomV1 {
+    // rooms of version 1 could not be delisted and had no attributes, they stay listed
+    // with the default attributes until their landlord updates them
+    pub fn migrate(self) -> Room {
+        Room {
+            room_id: self.room_id,
+            agreement_id: self.agreement_id,
+            room_name: self.room_name,
+            room_address: self.room_address,
+            rent_per_month: self.rent_per_month,
+            security_deposit: self.security_deposit,
+            time_stamp: self.time_stamp,
+            vacant: self.vacant,
+            landlord: self.landlord,
+            current_tenant: self.current_tenant,
+            next_rent_due_date: self.next_rent_due_date,
+            billing_period: self.billing_period,
+            late_fee_policy: self.late_fee_policy,
+            lease_terms: self.lease_terms,
+            listed: true,
+            attributes: Default::default(),
+        }
+    }
+}
+
+impl RoomAgreementV1 {
+    // agreements of version 1 ran on the terms of their room, which are kept with them
+    pub fn migrate(
+        self,
+        billing_period: Timestamp,
+        late_fee_policy: LateFeePolicy,
+    ) -> RoomAgreement {
+        RoomAgreement {
+            room_id: self.room_id,
+            agreement_id: self.agreement_id,
+            room_name: self.room_name,
+            room_address: self.room_address,
+            rent_per_month: self.rent_per_month,
+            security_deposit: self.security_deposit,
+            lock_in_period: self.lock_in_period,
+            time_stamp: self.time_stamp,
+            start_time: self.start_time,
+            end_time: self.end_time,
+            early_exit_penalty: self.early_exit_penalty,
+            tenant: self.tenant,
+            landlord: self.landlord,
+            currency: self.currency,
+            billing_period,
+            late_fee_policy,
+        }
+    }
+}
+
+impl ProposalV1 {
+    // proposals of version 1 acted on whatever agreement the room had when they ran, they
+    // are bound to `agreement_id`, the current agreement of the room, and rent changes keep
+    // the `security_deposit` of the room
+    pub fn migrate(self, agreement_id: AgreementId, security_deposit: Balance) -> Proposal {
+        let action = match self.action {
+            ProposalActionV1::TerminateAgreement { room_id } => {
+                ProposalAction::TerminateAgreement {
+                    room_id,
+                    agreement_id,
+                }
+            }
+            ProposalActionV1::ForfeitDeposit { room_id, amount } => {
+                ProposalAction::ForfeitDeposit {
+                    room_id,
+                    agreement_id,
+                    amount,
+                }
+            }
+            ProposalActionV1::ChangeRent {
+                room_id,
+                rent_per_month,
+            } => ProposalAction::ChangeRent {
+                room_id,
+                rent_per_month,
+                security_deposit,
+            },
+        };
+
+        Proposal {
+            proposal_id: self.proposal_id,
+            action,
+            proposer: self.proposer,
+            approvals: self.approvals,
+            deadline: self.deadline,
+            status: self.status,
+            time_stamp: self.time_stamp,
+        }
+    }
+}
+
+// value decoded only if it spans the whole stored record, so a record is never read with
+// a layout it merely starts with and converting a record twice is not possible
+struct Exact<V>(V);
+
+impl<V: scale::Encode> scale::Encode for Exact<V> {
+    fn size_hint(&self) -> usize {
+        self.0.size_hint()
+    }
+
+    fn encode_to<O: scale::Output + ?Sized>(&self, dest: &mut O) {
+        self.0.encode_to(dest)
+    }
+}
+
+impl<V: scale::Decode> scale::Decode for Exact<V> {
+    fn decode<I: scale::Input>(input: &mut I) -> Result<Self, scale::Error> {
+        let value = V::decode(input)?;
+        match input.remaining_len()? {
+            Some(0) => Ok(Exact(value)),
+            _ => Err("record is longer than the layout".into()),
+        }
+    }
+}
+
+// read the value stored under `key` of the mapping at `mapping_key` with the layout `V`,
+// records that aren't stored with exactly that layout are skipped
+pub fn read_raw<K, V>(mapping_key: Key, key: &K) -> Option<V>
+where
+    K: scale::Encode,
+    V: scale::Decode + scale::Encode,
+{
+    ink::env::get_contract_storage::<_, Exact<V>>(&(&mapping_key, key))
+        .ok()
+        .flatten()
+        .map(|value| value.0)
+}
@@ -1,2 +1,3 @@
+pub mod migration;
 pub mod room_book;
 pub mod types;
@@ -1,15 +1,32 @@
+use crate::impls::room_book::migration::{read_raw, ProposalV1, RoomAgreementV1, RoomV1};
 pub use crate::{
     ensure,
     impls::room_book::types::{
-        AgreementId, Data, HotelError, Rent, RentId, Room, RoomAgreement, RoomId,
+        is_mime_type, AgreementId, Ban, BanTerms, ContentId, Currency, Data, HotelError,
+        LateFeePolicy, LeaseTerms, PriceChange, Proposal, ProposalAction, ProposalId, ProposalPage,
+        ProposalStatus, Reconciliation, Rent, RentId, RentStatus, Room, RoomAgreement,
+        RoomAttributes, RoomFilter, RoomId, RoomListing, RoomMetadata, RoomPage, Verification,
+        VerificationStatus, ACCOUNTANT, ATTESTER, BASIS_POINTS, DAY, DEFAULT_BILLING_PERIOD,
+        DEFAULT_NOTICE_PERIOD, FRONT_DESK, MANAGER, MAX_PAGE_SCAN, MAX_PAGE_SIZE,
+        RENT_PAYMENT_WINDOW, STORAGE_VERSION,
     },
     traits::room_book::*,
 };
-use ink::prelude::{string::String, vec::Vec};
+use ink::{
+    prelude::{format, string::String, vec::Vec},
+    storage::traits::StorageKey,
+};
 use openbrush::{
-    contracts::ownable::*,
+    contracts::{
+        access_control::{self, AccessControl, RoleType},
+        ownable::*,
+        pausable,
+        psp22::PSP22Ref,
+        psp34::{self, extensions::metadata, Id},
+        reentrancy_guard::{self, non_reentrant},
+    },
     modifier_definition, modifiers,
-    traits::{AccountId, Storage, Timestamp, ZERO_ADDRESS},
+    traits::{AccountId, Balance, Storage, Timestamp, ZERO_ADDRESS},
 };
 
 use super::types::RoomResult;
@@ -17,17 +34,84 @@ use super::types::RoomResult;
 // Events for Hotel room booking
 pub trait HotelRoomBookingEvents {
     fn emit_add_room_event(&self, room_id: RoomId, owner: AccountId);
-    fn emit_sign_agreement_event(&self, room_id: RoomId, agreement_signer: AccountId);
-    fn emit_rent_payment_event(&self, room_id: RoomId, rent_payment_signer: AccountId);
+    fn emit_sign_agreement_event(
+        &self,
+        room_id: RoomId,
+        agreement_signer: AccountId,
+        credited: Balance,
+    );
+    fn emit_rent_payment_event(
+        &self,
+        room_id: RoomId,
+        rent_payment_signer: AccountId,
+        credited: Balance,
+    );
     fn emit_agreement_complete_event(&self, room_id: RoomId);
     fn emit_agreement_terminated_event(&self, room_id: RoomId);
+    fn emit_agreement_exited_event(&self, room_id: RoomId, tenant: AccountId, penalty: Balance);
+    fn emit_claim_credited_event(&self, account: AccountId, amount: Balance, currency: Currency);
+    fn emit_withdrawal_event(&self, account: AccountId, amount: Balance, currency: Currency);
+    fn emit_deposit_deducted_event(&self, room_id: RoomId, amount: Balance);
+    fn emit_landlord_registered_event(&self, landlord: AccountId);
+    fn emit_landlord_removed_event(&self, landlord: AccountId);
+    fn emit_tenant_attested_event(
+        &self,
+        account: AccountId,
+        status: VerificationStatus,
+        expiry: Timestamp,
+    );
+    fn emit_tenant_banned_event(
+        &self,
+        account: AccountId,
+        reason_code: u32,
+        expiry: Option<Timestamp>,
+    );
+    fn emit_tenant_unbanned_event(&self, account: AccountId);
+    fn emit_landlord_proposed_event(&self, landlord: AccountId, proposed: AccountId);
+    fn emit_landlordship_transferred_event(&self, previous: AccountId, new: AccountId);
+    fn emit_proposal_created_event(&self, proposal_id: ProposalId, proposer: AccountId);
+    fn emit_proposal_approved_event(&self, proposal_id: ProposalId, signer: AccountId);
+    fn emit_proposal_executed_event(&self, proposal_id: ProposalId);
+    fn emit_rent_change_scheduled_event(
+        &self,
+        room_id: RoomId,
+        rent_per_month: Balance,
+        security_deposit: Balance,
+        effective_at: Timestamp,
+    );
+    fn emit_contract_upgraded_event(&self, code_hash: [u8; 32]);
+    fn emit_storage_migrated_event(&self, from_version: u32, to_version: u32);
+    fn emit_surplus_withdrawn_event(
+        &self,
+        account: AccountId,
+        amount: Balance,
+        contract_balance: Balance,
+        liabilities: Balance,
+    );
+    fn emit_room_updated_event(&self, room_id: RoomId);
+    fn emit_room_removed_event(&self, room_id: RoomId);
+    fn emit_billing_period_changed_event(&self, room_id: RoomId, billing_period: Timestamp);
+    fn emit_late_fee_policy_changed_event(&self, room_id: RoomId, policy: LateFeePolicy);
+    fn emit_lease_terms_changed_event(&self, room_id: RoomId, terms: LeaseTerms);
+    fn emit_room_metadata_updated_event(
+        &self,
+        room_id: RoomId,
+        content: ContentId,
+        mime_type: String,
+    );
 }
 
 impl<T> RoomBook for T
 where
-    T: Storage<Data> + Storage<ownable::Data>,
+    T: Storage<Data>
+        + Storage<ownable::Data>
+        + Storage<access_control::Data>
+        + Storage<pausable::Data>
+        + Storage<reentrancy_guard::Data>
+        + Storage<psp34::Data>
+        + Storage<metadata::Data>,
 {
-    #[modifiers(only_owner)]
+    #[modifiers(non_reentrant, is_landlord_or_role(MANAGER))]
     default fn add_room(
         &mut self,
         room_name: String,
@@ -35,16 +119,26 @@ where
         rent_per_month: u128,
         security_deposit: u128,
         time_stamp: Timestamp,
+        attributes: RoomAttributes,
     ) -> RoomResult {
         // caller of the contract
         let caller = T::env().caller();
 
-        // check validation for `room_name` length, `room_address` length,
-        // `rent_per_month` and `security_deposit`
-        ensure!(room_name.len() > 4, HotelError::InvalidRoomLength);
-        ensure!(room_address.len() > 4, HotelError::InvalidAddressLength);
-        ensure!(rent_per_month > 0, HotelError::InvalidRentPerMonth);
-        ensure!(security_deposit > 0, HotelError::InvalidSecurityDeposit);
+        self.validate_room(
+            &room_name,
+            &room_address,
+            rent_per_month,
+            security_deposit,
+            &attributes,
+        )?;
+
+        // rooms of registered landlords belong to them, rooms added by the owner or the
+        // hotel staff are held by the contract and follow its ownership
+        let landlord = if self.is_landlord(caller) && caller != self.owner() {
+            caller
+        } else {
+            T::env().account_id()
+        };
 
         // get `room_id` & `agreement_id`
         let room_id = self.next_room_id();
@@ -60,36 +154,252 @@ where
             security_deposit,
             time_stamp,
             vacant: true,
-            landlord: caller,
+            landlord,
             current_tenant: ZERO_ADDRESS.into(),
             next_rent_due_date: Timestamp::from(0u64),
+            billing_period: DEFAULT_BILLING_PERIOD,
+            late_fee_policy: LateFeePolicy::default(),
+            lease_terms: LeaseTerms::default(),
+            listed: true,
+            attributes,
         };
 
         // insert room in `Mapping` with respect to key `room_id`
         self.data::<Data>().room.insert(&room_id, &new_room);
+        let room_count = self.room_count();
+        self.data::<Data>().room_count.set(&(room_count + 1));
+        self.track_availability(room_id, false, true);
+        self.index_landlord_room(landlord, room_id, true);
 
         // event call
-        self.emit_add_room_event(room_id, caller);
+        self.emit_add_room_event(room_id, self.room_landlord(&new_room));
 
         Ok(room_id)
     }
 
-    #[modifiers(is_normal_user)]
-    default fn sign_agreement(&mut self, room_id: RoomId) -> RoomResult {
-        // caller of the contract
-        let caller = T::env().caller();
+    default fn validate_room(
+        &self,
+        room_name: &str,
+        room_address: &str,
+        rent_per_month: u128,
+        security_deposit: u128,
+        attributes: &RoomAttributes,
+    ) -> Result<(), HotelError> {
+        // check validation for `room_name` length, `room_address` length,
+        // `rent_per_month` and `security_deposit`
+        ensure!(room_name.len() > 4, HotelError::InvalidRoomLength);
+        ensure!(room_address.len() > 4, HotelError::InvalidAddressLength);
+        ensure!(rent_per_month > 0, HotelError::InvalidRentPerMonth);
+        ensure!(security_deposit > 0, HotelError::InvalidSecurityDeposit);
 
-        // value transfer while calling contract
-        let value = T::env().transferred_value();
+        // room must sleep at least one occupant
+        ensure!(
+            attributes.max_occupants > 0 && attributes.beds > 0,
+            HotelError::InvalidRoomAttributes
+        );
+
+        Ok(())
+    }
+
+    #[modifiers(non_reentrant, only_room_landlord_or_role(room_id, MANAGER))]
+    default fn update_room(
+        &mut self,
+        room_id: RoomId,
+        room_name: String,
+        room_address: String,
+        rent_per_month: u128,
+        security_deposit: u128,
+        attributes: RoomAttributes,
+    ) -> RoomResult {
+        let mut room = match self.data::<Data>().room.get(&room_id) {
+            Some(value) => value,
+            None => return Err(HotelError::RoomNotFound),
+        };
+
+        self.validate_room(
+            &room_name,
+            &room_address,
+            rent_per_month,
+            security_deposit,
+            &attributes,
+        )?;
+
+        let price_changed =
+            room.rent_per_month != rent_per_month || room.security_deposit != security_deposit;
+
+        // new prices of a room of the hotel need the approval of the signers once they are set
+        ensure!(
+            !price_changed
+                || self.data::<Data>().signers.is_empty()
+                || room.landlord != T::env().account_id(),
+            HotelError::ProposalRequired
+        );
+
+        room.room_name = room_name;
+        room.room_address = room_address;
+        room.attributes = attributes;
+
+        // tenant of an occupied room gets the notice period before new prices apply
+        if price_changed && !room.vacant {
+            let effective_at = T::env().block_timestamp() + self.data::<Data>().notice_period;
+            self.schedule_change(room_id, rent_per_month, security_deposit, effective_at)?;
+        } else if price_changed {
+            room.rent_per_month = rent_per_month;
+            room.security_deposit = security_deposit;
+            self.data::<Data>().price_changes.remove(&room_id);
+        }
+
+        self.data::<Data>().room.insert(&room_id, &room);
+
+        self.emit_room_updated_event(room_id);
+
+        Ok(room_id)
+    }
+
+    #[modifiers(non_reentrant, only_room_landlord_or_role(room_id, MANAGER))]
+    default fn delist_room(&mut self, room_id: RoomId) -> RoomResult {
+        let mut room = match self.data::<Data>().room.get(&room_id) {
+            Some(value) => value,
+            None => return Err(HotelError::RoomNotFound),
+        };
+
+        let was_available = room.is_available();
+        room.listed = false;
+
+        self.data::<Data>().room.insert(&room_id, &room);
+        self.track_availability(room_id, was_available, false);
+
+        self.emit_room_updated_event(room_id);
 
-        // get the romm of specific `room_id`
+        Ok(room_id)
+    }
+
+    #[modifiers(non_reentrant, only_room_landlord_or_role(room_id, MANAGER))]
+    default fn relist_room(&mut self, room_id: RoomId) -> RoomResult {
         let mut room = match self.data::<Data>().room.get(&room_id) {
             Some(value) => value,
             None => return Err(HotelError::RoomNotFound),
         };
 
+        let was_available = room.is_available();
+        room.listed = true;
+
+        self.data::<Data>().room.insert(&room_id, &room);
+        self.track_availability(room_id, was_available, room.is_available());
+
+        self.emit_room_updated_event(room_id);
+
+        Ok(room_id)
+    }
+
+    #[modifiers(non_reentrant, only_room_landlord_or_role(room_id, MANAGER))]
+    default fn set_room_metadata(
+        &mut self,
+        room_id: RoomId,
+        content: ContentId,
+        mime_type: String,
+    ) -> RoomResult {
+        ensure!(
+            self.data::<Data>().room.contains(&room_id),
+            HotelError::RoomNotFound
+        );
+
+        // check the content identifier and the media type before storing them
+        ensure!(content.is_valid(), HotelError::InvalidContentId);
+        ensure!(is_mime_type(&mime_type), HotelError::InvalidMimeType);
+
+        let metadata = RoomMetadata {
+            content: content.clone(),
+            mime_type: mime_type.clone(),
+            time_stamp: T::env().block_timestamp(),
+        };
+        self.data::<Data>()
+            .room_metadata
+            .insert(&room_id, &metadata);
+
+        self.emit_room_metadata_updated_event(room_id, content, mime_type);
+
+        Ok(room_id)
+    }
+
+    default fn get_room_metadata(&self, room_id: RoomId) -> Option<RoomMetadata> {
+        self.data::<Data>().room_metadata.get(&room_id)
+    }
+
+    #[modifiers(non_reentrant, only_room_landlord_or_role(room_id, MANAGER))]
+    default fn remove_room(&mut self, room_id: RoomId) -> RoomResult {
+        let room = match self.data::<Data>().room.get(&room_id) {
+            Some(value) => value,
+            None => return Err(HotelError::RoomNotFound),
+        };
+
+        // room can only be removed once its last agreement has ended
+        ensure!(room.vacant == true, HotelError::RoomIsNotVacant);
+
+        self.data::<Data>().room.remove(&room_id);
+        self.data::<Data>().price_changes.remove(&room_id);
+        self.data::<Data>().room_metadata.remove(&room_id);
+        let room_count = self.room_count();
+        self.data::<Data>().room_count.set(&(room_count - 1));
+        self.track_availability(room_id, room.is_available(), false);
+        self.index_landlord_room(room.landlord, room_id, false);
+
+        self.emit_room_removed_event(room_id);
+
+        Ok(room_id)
+    }
+
+    #[modifiers(
+        non_reentrant,
+        not_paused,
+        is_normal_user(room_id),
+        is_verified_tenant,
+        is_not_banned
+    )]
+    default fn sign_agreement(&mut self, room_id: RoomId) -> RoomResult {
+        // value transfer while calling contract
+        let value = T::env().transferred_value();
+
+        self.book_room(room_id, value, Currency::Native)
+    }
+
+    #[modifiers(
+        non_reentrant,
+        not_paused,
+        is_normal_user(room_id),
+        is_verified_tenant,
+        is_not_banned
+    )]
+    default fn sign_agreement_with_token(&mut self, room_id: RoomId) -> RoomResult {
+        let caller = T::env().caller();
+        let token = self.accepted_token()?;
+
+        let room = self.apply_price_change(room_id)?;
+
+        // exactly `total_fee` is pulled from the tenant, so nothing is overpaid
+        let total_fee = room.rent_per_month + room.security_deposit;
+        self.book_room(room_id, total_fee, Currency::Psp22(token))?;
+
+        // pull the fee once the agreement is recorded, tenant must approve it beforehand
+        self.pull_token(token, caller, total_fee)?;
+
+        Ok(room_id)
+    }
+
+    default fn book_room(
+        &mut self,
+        room_id: RoomId,
+        value: Balance,
+        currency: Currency,
+    ) -> RoomResult {
+        // caller of the contract
+        let caller = T::env().caller();
+
+        // get the romm of specific `room_id` with the prices of the new agreement
+        let mut room = self.apply_price_change(room_id)?;
+
         // get the room `landlord`
-        let room_landlord = room.landlord;
+        let room_landlord = self.room_landlord(&room);
 
         // get the total to sign the agreement
         let total_fee = room.rent_per_month + room.security_deposit;
@@ -100,50 +410,102 @@ where
         // room must be `vacant` to pass the agreement
         ensure!(room.vacant == true, HotelError::RoomIsNotVacant);
 
-        // transfer `total_fee` to `landlord`
-        Self::env()
-            .transfer(room_landlord, total_fee)
-            .unwrap_or_default();
+        // delisted room takes no new agreements
+        ensure!(room.listed, HotelError::RoomNotListed);
 
         // get the `next_room_agreement_id`
         let agreement_id = self.next_agreement_id();
 
+        // credit first month rent to the `landlord` claims and keep
+        // `security_deposit` in escrow until the agreement ends
+        self.credit_claim(room_landlord, room.rent_per_month, currency);
+        self.hold_deposit(agreement_id, room.security_deposit, currency);
+
+        // keep any overpayment as tenant credit for the next `pay_rent`
+        let credited = value - total_fee;
+        self.add_tenant_credit(caller, credited);
+
         room.room_id = room_id;
         room.agreement_id = agreement_id;
         room.vacant = false;
         room.current_tenant = caller;
+        self.track_availability(room_id, true, false);
+        self.index_tenant_room(caller, room_id, true);
+
+        // first month is paid with the agreement, next rent is due one billing period later
+        let now = T::env().block_timestamp();
+        room.next_rent_due_date = now + room.billing_period;
 
         self.data::<Data>().room.insert(&room_id, &room);
 
-        // create new `RoomAgreement` object with given fields
-        let mut agreement = match self.data::<Data>().agreement.get(&agreement_id) {
-            Some(value) => value,
-            None => return Err(HotelError::RoomNotFound),
+        // create new `RoomAgreement` object from the room, it runs on the
+        // lease terms of the room at signing time
+        let agreement = RoomAgreement {
+            room_id,
+            agreement_id,
+            room_name: room.room_name.clone(),
+            room_address: room.room_address.clone(),
+            rent_per_month: room.rent_per_month,
+            security_deposit: room.security_deposit,
+            lock_in_period: room.lease_terms.lock_in_period,
+            time_stamp: now,
+            start_time: now,
+            end_time: now + room.lease_terms.lease_duration,
+            early_exit_penalty: room.lease_terms.early_exit_penalty,
+            tenant: caller,
+            landlord: room_landlord,
+            currency,
+            billing_period: room.billing_period,
+            late_fee_policy: room.late_fee_policy.clone(),
         };
 
-        agreement.room_id = room_id;
-        agreement.agreement_id = agreement_id;
-        agreement.lock_in_period = 1;
-
         // insert room `sign_agreement` to the agreement mapping
         self.data::<Data>()
             .agreement
             .insert(&agreement_id, &agreement);
 
+        // tenant receives the lease as a PSP34 token with the agreement id
+        self.mint_lease(&agreement)?;
+
+        // keep the agreement history of the room and the tenant
+        let mut room_agreements = self
+            .data::<Data>()
+            .room_agreements
+            .get(&room_id)
+            .unwrap_or_default();
+        room_agreements.push(agreement_id);
+        self.data::<Data>()
+            .room_agreements
+            .insert(&room_id, &room_agreements);
+
+        let mut tenant_agreements = self
+            .data::<Data>()
+            .tenant_agreements
+            .get(&caller)
+            .unwrap_or_default();
+        tenant_agreements.push(agreement_id);
+        self.data::<Data>()
+            .tenant_agreements
+            .insert(&caller, &tenant_agreements);
+
         // get the `next_rent_id`
         let rent_id = self.next_rent_id();
 
-        // create new `Rent` object with the given fields
-        let mut rent = match self.data::<Data>().rent.get(&rent_id) {
-            Some(value) => value,
-            None => return Err(HotelError::RoomNotFound),
+        // create new `Rent` object for the first month paid with the agreement
+        let rent = Rent {
+            rent_id,
+            room_id,
+            agreement_id,
+            room_name: room.room_name,
+            room_address: room.room_address,
+            rent_per_month: room.rent_per_month,
+            time_stamp: now,
+            tenant_address: caller,
+            land_lord_address: room_landlord,
+            late_fee: 0,
+            currency,
         };
 
-        rent.rent_id = rent_id;
-        rent.room_id = room_id;
-        rent.tenant_address = caller;
-        rent.land_lord_address = room_landlord;
-
         // insert `Rent` in the rent mapping
         self.data::<Data>().rent.insert(&rent_id, &rent);
 
@@ -158,65 +520,122 @@ where
             .insert(&caller, &(rent_count + 1));
 
         // call the event
-        self.emit_sign_agreement_event(room_id, caller);
+        self.emit_sign_agreement_event(room_id, caller, credited);
 
         Ok(room_id)
     }
 
+    #[modifiers(non_reentrant, not_paused)]
     default fn pay_rent(&mut self, room_id: RoomId) -> RoomResult {
-        let caller = T::env().caller();
         let value = T::env().transferred_value();
 
-        // get the room and check whether it exists or not
-        let mut room = match self.data::<Data>().room.get(&room_id) {
-            Some(value) => value,
-            None => return Err(HotelError::RoomNotFound),
-        };
+        self.collect_rent(room_id, value, Currency::Native)
+    }
+
+    #[modifiers(non_reentrant, not_paused)]
+    default fn pay_rent_with_token(&mut self, room_id: RoomId) -> RoomResult {
+        let caller = T::env().caller();
+        let token = self.accepted_token()?;
+
+        let room = self.apply_price_change(room_id)?;
+
+        // exactly `rent_per_month` and `late_fee` are pulled from the tenant
+        let policy = self.agreement_late_fee_policy(&room);
+        let required = room.rent_per_month + room.late_fee(&policy, T::env().block_timestamp());
+        self.collect_rent(room_id, required, Currency::Psp22(token))?;
+
+        // pull the rent once it is recorded, tenant must approve it beforehand
+        self.pull_token(token, caller, required)?;
+
+        Ok(room_id)
+    }
+
+    default fn collect_rent(
+        &mut self,
+        room_id: RoomId,
+        value: Balance,
+        currency: Currency,
+    ) -> RoomResult {
+        let caller = T::env().caller();
+
+        // get the room with the rent of the billing cycle being paid
+        let mut room = self.apply_price_change(room_id)?;
 
         // check `caller` is same as `room.tenant_address`
         ensure!(caller == room.current_tenant, HotelError::NotATenantAddress);
 
-        // check `rent` is enough to pay
-        ensure!(value >= room.rent_per_month, HotelError::NotEnoughRentFee);
+        let now = T::env().block_timestamp();
+
+        // rent can only be paid once the payment window of the next due date is open
+        ensure!(
+            now + RENT_PAYMENT_WINDOW >= room.next_rent_due_date,
+            HotelError::RentNotDue
+        );
 
-        // check if it is time to pay rent for the room
-        let land_lord = room.landlord;
-        let rent = room.rent_per_month;
+        // late fee accrued since the grace period of the due date ended
+        let late_fee = room.late_fee(&self.agreement_late_fee_policy(&room), now);
+        let required = room.rent_per_month + late_fee;
 
-        // transfer `rent` to the `land_lord`
-        Self::env().transfer(land_lord, rent).unwrap_or_default();
+        // tenant credit from earlier overpayments is applied to the rent paid in native value
+        let credit = match currency {
+            Currency::Native => self.get_tenant_credit(caller),
+            Currency::Psp22(_) => 0,
+        };
+        let available = value + credit;
+
+        // check `rent` and `late_fee` are enough to pay
+        ensure!(
+            available >= required,
+            HotelError::InsufficientRentFee(required.saturating_sub(credit))
+        );
+
+        let land_lord = self.room_landlord(&room);
+
+        // whatever is left over stays as tenant credit
+        let credited = available - required;
+        if currency == Currency::Native {
+            self.data::<Data>().tenant_credit.insert(&caller, &credited);
+            self.track_tenant_credit(caller, credit, credited);
+        }
+
+        // credit `rent` and `late_fee` to the `land_lord` claims
+        self.credit_claim(land_lord, required, currency);
 
         room.vacant = false;
         room.current_tenant = caller;
 
+        // paid rent covers one more billing period of the agreement
+        room.next_rent_due_date += self.agreement_billing_period(&room);
+
         self.data::<Data>().room.insert(&room_id, &room);
 
-        // get the `rent_id` & `agreement_id`
+        // get the `rent_id`
         let rent_id = self.next_rent_id();
-        let agreement_id = self.next_agreement_id();
 
-        // create new `Rent` object with the given fields
+        // create new `Rent` object for the current agreement of the room
         let rent = Rent {
             rent_id,
             room_id,
-            agreement_id,
+            agreement_id: room.agreement_id,
             room_name: room.room_name,
             room_address: room.room_address,
             rent_per_month: room.rent_per_month,
-            time_stamp: room.time_stamp,
+            time_stamp: now,
             tenant_address: caller,
             land_lord_address: land_lord,
+            late_fee,
+            currency,
         };
 
         // insert `rent` to the mapping
         self.data::<Data>().rent.insert(&rent_id, &rent);
 
-        self.emit_rent_payment_event(room_id, caller);
+        self.emit_rent_payment_event(room_id, caller, credited);
 
         Ok(room_id)
     }
 
-    #[modifiers(only_owner)]
+    #[modifiers(non_reentrant, only_room_landlord_or_role(room_id, FRONT_DESK))]
     default fn agreement_completed(&mut self, room_id: RoomId) -> RoomResult {
         let mut room = match self.data::<Data>().room.get(&room_id) {
             Some(value) => value,
@@ -226,124 +645,2043 @@ where
         // check if room is not vacant
         ensure!(room.vacant == false, HotelError::RoomIsVacant);
 
-        // get the `current_tenant` & `security_deposit`
+        // agreement can only be completed once its end date is reached
+        let agreement = match self.get_agreement(room.agreement_id) {
+            Some(value) => value,
+            None => return Err(HotelError::AgreementNotFound),
+        };
+        ensure!(
+            T::env().block_timestamp() >= agreement.end_time,
+            HotelError::AgreementNotEnded
+        );
+
+        // get the `current_tenant` & held `security_deposit`
         let current_tenant = room.current_tenant;
-        let security_deposit = room.security_deposit;
+        let security_deposit = self.get_agreement_deposit(room.agreement_id);
 
-        // transfer `security_deposit` to  the `current_tenant` after complete agreement
-        Self::env()
-            .transfer(current_tenant, security_deposit)
-            .unwrap_or_default();
+        // release `security_deposit` to the `current_tenant` claims after complete agreement
+        self.release_deposit(room.agreement_id, security_deposit, current_tenant);
 
+        self.index_tenant_room(room.current_tenant, room_id, false);
         room.vacant = true;
         room.current_tenant = ZERO_ADDRESS.into();
+        self.track_availability(room_id, false, room.is_available());
 
         self.data::<Data>().room.insert(&room_id, &room);
 
+        self.burn_lease(room.agreement_id)?;
+
         self.emit_agreement_complete_event(room_id);
 
         Ok(room_id)
     }
 
-    #[modifiers(only_owner)]
-    default fn agreement_terminated(&mut self, room_id: RoomId) -> RoomResult {
+    #[modifiers(non_reentrant)]
+    default fn exit_agreement(&mut self, room_id: RoomId) -> RoomResult {
+        let caller = T::env().caller();
+
         let mut room = match self.data::<Data>().room.get(&room_id) {
             Some(value) => value,
             None => return Err(HotelError::RoomNotFound),
         };
 
-        // can only terminate agreement if room is not vacant
+        // only the current tenant can leave the room
         ensure!(room.vacant == false, HotelError::RoomIsVacant);
+        ensure!(caller == room.current_tenant, HotelError::NotATenantAddress);
+
+        let agreement = match self.get_agreement(room.agreement_id) {
+            Some(value) => value,
+            None => return Err(HotelError::AgreementNotFound),
+        };
+
+        let now = T::env().block_timestamp();
+        let landlord = self.room_landlord(&room);
+
+        // unpaid rent of every started billing cycle and its late fee are settled
+        // from the deposit before the tenant leaves
+        if now > room.next_rent_due_date {
+            let cycles = (now - room.next_rent_due_date) / agreement.billing_period + 1;
+            let mut rent_per_month = room.rent_per_month;
+            if let Some(change) = self.pending_change_due(&room) {
+                rent_per_month = change.rent_per_month;
+            }
+            let arrears = rent_per_month
+                .saturating_mul(cycles as Balance)
+                .saturating_add(room.late_fee(&agreement.late_fee_policy, now));
+            self.release_deposit(room.agreement_id, arrears, landlord);
+        }
+
+        // leaving during the lock-in period costs the `early_exit_penalty`
+        let mut penalty = 0;
+        if now < agreement.start_time + agreement.lock_in_period {
+            penalty = agreement
+                .early_exit_penalty
+                .min(self.get_agreement_deposit(room.agreement_id));
+            self.release_deposit(room.agreement_id, penalty, landlord);
+        }
+
+        // rest of the deposit goes back to the tenant
+        let security_deposit = self.get_agreement_deposit(room.agreement_id);
+        self.release_deposit(room.agreement_id, security_deposit, caller);
 
+        self.index_tenant_room(room.current_tenant, room_id, false);
         room.vacant = true;
         room.current_tenant = ZERO_ADDRESS.into();
+        self.track_availability(room_id, false, room.is_available());
 
         self.data::<Data>().room.insert(&room_id, &room);
-        self.emit_agreement_terminated_event(room_id);
-        Ok(room_id)
-    }
 
-    // owner of the contract allowed to view all the rooms
-    #[modifiers(only_owner)]
-    default fn get_room(&mut self) -> Result<Vec<Room>, HotelError> {
-        let mut room: Vec<Room> = Vec::new();
-        for room_id in 0..self.data::<Data>().room_id {
-            match self.data::<Data>().room.get(&room_id) {
-                Some(value) => room.push(value),
-                None => (),
-            }
-        }
+        self.burn_lease(room.agreement_id)?;
 
-        Ok(room)
+        self.emit_agreement_exited_event(room_id, caller, penalty);
+
+        Ok(room_id)
     }
 
-    // get how many times did user rent room
-    fn get_room_rent_count(&self, user: AccountId) -> Option<i32> {
-        self.data::<Data>().room_rent_quantity.get(&user)
+    #[modifiers(
+        non_reentrant,
+        only_room_landlord_or_role(room_id, FRONT_DESK),
+        without_multisig(room_id)
+    )]
+    default fn agreement_terminated(
+        &mut self,
+        room_id: RoomId,
+        ban: Option<BanTerms>,
+    ) -> RoomResult {
+        self.terminate_agreement(room_id, ban)
     }
 
-    // get the available rooms from the hotel
-    default fn get_available_room(&self) -> Vec<Room> {
-        let mut room: Vec<Room> = Vec::new();
-        for room_id in 0..self.data::<Data>().room_id {
-            match self.data::<Data>().room.get(&room_id) {
-                Some(value) => {
-                    if value.vacant == true {
-                        room.push(value)
-                    }
-                }
-                None => (),
+    default fn terminate_agreement(
+        &mut self,
+        room_id: RoomId,
+        ban: Option<BanTerms>,
+    ) -> RoomResult {
+        let mut room = match self.data::<Data>().room.get(&room_id) {
+            Some(value) => value,
+            None => return Err(HotelError::RoomNotFound),
+        };
+
+        // can only terminate agreement if room is not vacant
+        ensure!(room.vacant == false, HotelError::RoomIsVacant);
+
+        // ban the tenant from signing new agreements
+        if let Some(terms) = ban {
+            let now = T::env().block_timestamp();
+            if let Some(expiry) = terms.expiry {
+                ensure!(expiry > now, HotelError::InvalidBanExpiry);
             }
-        }
 
-        room
-    }
+            let ban = Ban {
+                reason_code: terms.reason_code,
+                room_id,
+                banned_by: T::env().caller(),
+                time_stamp: now,
+                expiry: terms.expiry,
+            };
+            self.data::<Data>().bans.insert(&room.current_tenant, &ban);
 
-    fn get_landlord(&self) -> AccountId {
-        self.data::<Data>().land_lord.clone()
-    }
+            self.emit_tenant_banned_event(room.current_tenant, terms.reason_code, terms.expiry);
+        }
 
-    default fn next_room_id(&mut self) -> RoomId {
-        let room_id = self.data::<Data>().room_id;
-        self.data::<Data>().room_id += 1;
-        room_id
-    }
+        // whatever is left of the deposit after deductions goes back to the tenant
+        let security_deposit = self.get_agreement_deposit(room.agreement_id);
+        self.release_deposit(room.agreement_id, security_deposit, room.current_tenant);
 
-    default fn next_agreement_id(&mut self) -> AgreementId {
-        let agreement_id = self.data::<Data>().agreement_id;
-        self.data::<Data>().agreement_id += 1;
-        agreement_id
-    }
+        self.index_tenant_room(room.current_tenant, room_id, false);
+        room.vacant = true;
+        room.current_tenant = ZERO_ADDRESS.into();
+        self.track_availability(room_id, false, room.is_available());
 
-    default fn next_rent_id(&mut self) -> RentId {
-        let rent_id = self.data::<Data>().rent_id;
-        self.data::<Data>().rent_id += 1;
-        rent_id
+        self.data::<Data>().room.insert(&room_id, &room);
+        self.burn_lease(room.agreement_id)?;
+
+        self.emit_agreement_terminated_event(room_id);
+        Ok(room_id)
     }
-}
 
-impl<T> HotelRoomBookingEvents for T
+    #[modifiers(non_reentrant, only_room_landlord_or_role(room_id, MANAGER))]
+    default fn set_billing_period(
+        &mut self,
+        room_id: RoomId,
+        billing_period: Timestamp,
+    ) -> RoomResult {
+        let mut room = match self.data::<Data>().room.get(&room_id) {
+            Some(value) => value,
+            None => return Err(HotelError::RoomNotFound),
+        };
+
+        ensure!(billing_period > 0, HotelError::InvalidBillingPeriod);
+
+        // the current agreement keeps the billing period it was signed with
+        room.billing_period = billing_period;
+
+        self.data::<Data>().room.insert(&room_id, &room);
+
+        self.emit_billing_period_changed_event(room_id, billing_period);
+
+        Ok(room_id)
+    }
+
+    #[modifiers(non_reentrant, only_room_landlord_or_role(room_id, MANAGER))]
+    default fn set_late_fee_policy(
+        &mut self,
+        room_id: RoomId,
+        policy: LateFeePolicy,
+    ) -> RoomResult {
+        let mut room = match self.data::<Data>().room.get(&room_id) {
+            Some(value) => value,
+            None => return Err(HotelError::RoomNotFound),
+        };
+
+        ensure!(
+            policy.percentage_per_day <= BASIS_POINTS,
+            HotelError::InvalidLateFeePolicy
+        );
+
+        // the current agreement keeps the late fee policy it was signed with
+        room.late_fee_policy = policy.clone();
+
+        self.data::<Data>().room.insert(&room_id, &room);
+
+        self.emit_late_fee_policy_changed_event(room_id, policy);
+
+        Ok(room_id)
+    }
+
+    #[modifiers(non_reentrant, only_room_landlord_or_role(room_id, MANAGER))]
+    default fn set_lease_terms(&mut self, room_id: RoomId, terms: LeaseTerms) -> RoomResult {
+        let mut room = match self.data::<Data>().room.get(&room_id) {
+            Some(value) => value,
+            None => return Err(HotelError::RoomNotFound),
+        };
+
+        ensure!(
+            terms.lease_duration > 0 && terms.lock_in_period <= terms.lease_duration,
+            HotelError::InvalidLeaseTerms
+        );
+
+        // the current agreement keeps the lease terms it was signed with
+        room.lease_terms = terms.clone();
+
+        self.data::<Data>().room.insert(&room_id, &room);
+
+        self.emit_lease_terms_changed_event(room_id, terms);
+
+        Ok(room_id)
+    }
+
+    default fn get_agreement(&self, agreement_id: AgreementId) -> Option<RoomAgreement> {
+        self.data::<Data>().agreement.get(&agreement_id)
+    }
+
+    default fn agreement_billing_period(&self, room: &Room) -> Timestamp {
+        match self.get_agreement(room.agreement_id) {
+            Some(agreement) => agreement.billing_period,
+            None => room.billing_period,
+        }
+    }
+
+    default fn agreement_late_fee_policy(&self, room: &Room) -> LateFeePolicy {
+        match self.get_agreement(room.agreement_id) {
+            Some(agreement) => agreement.late_fee_policy,
+            None => room.late_fee_policy.clone(),
+        }
+    }
+
+    default fn get_agreements_for_room(&self, room_id: RoomId) -> Vec<RoomAgreement> {
+        self.data::<Data>()
+            .room_agreements
+            .get(&room_id)
+            .unwrap_or_default()
+            .into_iter()
+            .filter_map(|agreement_id| self.get_agreement(agreement_id))
+            .collect()
+    }
+
+    default fn get_agreements_for_tenant(&self, tenant: AccountId) -> Vec<RoomAgreement> {
+        self.data::<Data>()
+            .tenant_agreements
+            .get(&tenant)
+            .unwrap_or_default()
+            .into_iter()
+            .filter_map(|agreement_id| self.get_agreement(agreement_id))
+            .collect()
+    }
+
+    default fn get_rent_status(&self, room_id: RoomId) -> Result<RentStatus, HotelError> {
+        let mut room = match self.data::<Data>().room.get(&room_id) {
+            Some(value) => value,
+            None => return Err(HotelError::RoomNotFound),
+        };
+
+        // rent is only due while the room is occupied
+        ensure!(room.vacant == false, HotelError::RoomIsVacant);
+
+        // next payment is charged at the new rent once a scheduled change takes effect
+        if let Some(change) = self.pending_change_due(&room) {
+            room.rent_per_month = change.rent_per_month;
+        }
+
+        let now = T::env().block_timestamp();
+        let days_overdue = now.saturating_sub(room.next_rent_due_date) / DAY;
+
+        // late fee is added and tenant credit is applied to the next payment
+        let policy = self.agreement_late_fee_policy(&room);
+        let amount_due = (room.rent_per_month + room.late_fee(&policy, now))
+            .saturating_sub(self.get_tenant_credit(room.current_tenant));
+
+        Ok(RentStatus {
+            room_id,
+            next_rent_due_date: room.next_rent_due_date,
+            amount_due,
+            days_overdue,
+        })
+    }
+
+    #[modifiers(
+        non_reentrant,
+        only_room_landlord_or_role(room_id, ACCOUNTANT),
+        without_multisig(room_id)
+    )]
+    default fn deduct_deposit(&mut self, room_id: RoomId, amount: Balance) -> RoomResult {
+        self.forfeit_deposit(room_id, amount)
+    }
+
+    default fn forfeit_deposit(&mut self, room_id: RoomId, amount: Balance) -> RoomResult {
+        let room = match self.data::<Data>().room.get(&room_id) {
+            Some(value) => value,
+            None => return Err(HotelError::RoomNotFound),
+        };
+
+        // deposit is only held while the room is occupied
+        ensure!(room.vacant == false, HotelError::RoomIsVacant);
+        ensure!(amount > 0, HotelError::InvalidDeductionAmount);
+
+        let held = self.get_agreement_deposit(room.agreement_id);
+        ensure!(held >= amount, HotelError::InsufficientDeposit);
+
+        // credit the deducted amount to the room `landlord`
+        let landlord = self.room_landlord(&room);
+        self.release_deposit(room.agreement_id, amount, landlord);
+
+        self.emit_deposit_deducted_event(room_id, amount);
+
+        Ok(room_id)
+    }
+
+    default fn get_room_deposit(&self, room_id: RoomId) -> Balance {
+        match self.data::<Data>().room.get(&room_id) {
+            Some(room) if room.vacant == false => self.get_agreement_deposit(room.agreement_id),
+            _ => 0,
+        }
+    }
+
+    default fn get_agreement_deposit(&self, agreement_id: AgreementId) -> Balance {
+        self.data::<Data>()
+            .deposits
+            .get(&agreement_id)
+            .unwrap_or_default()
+    }
+
+    default fn get_total_escrow(&self) -> Balance {
+        self.data::<Data>().total_escrow
+    }
+
+    default fn get_token_escrow(&self, token: AccountId) -> Balance {
+        self.data::<Data>()
+            .token_escrow
+            .get(&token)
+            .unwrap_or_default()
+    }
+
+    // `ACCOUNTANT` allowed to view the first page of rooms
+    #[modifiers(only_hotel_role(ACCOUNTANT))]
+    default fn get_room(&mut self) -> Result<Vec<Room>, HotelError> {
+        Ok(self
+            .rooms_page(0, MAX_PAGE_SIZE, None)
+            .rooms
+            .into_iter()
+            .map(|listing| listing.room)
+            .collect())
+    }
+
+    // `ACCOUNTANT` allowed to page through all the rooms
+    #[modifiers(only_hotel_role(ACCOUNTANT))]
+    default fn get_rooms_page(
+        &mut self,
+        start: RoomId,
+        limit: u32,
+    ) -> Result<RoomPage, HotelError> {
+        Ok(self.rooms_page(start, limit, None))
+    }
+
+    default fn get_available_rooms_page(&self, start: RoomId, limit: u32) -> RoomPage {
+        self.rooms_page(start, limit, Some(&RoomFilter::default()))
+    }
+
+    default fn room_count(&self) -> u32 {
+        self.data::<Data>().room_count.get().unwrap_or_default()
+    }
+
+    default fn available_room_count(&self) -> u32 {
+        self.data::<Data>()
+            .available_room_count
+            .get()
+            .unwrap_or_default()
+    }
+
+    default fn rooms_page(
+        &self,
+        start: RoomId,
+        limit: u32,
+        filter: Option<&RoomFilter>,
+    ) -> RoomPage {
+        // an empty page can't move the cursor, there is no next page to point at
+        if limit == 0 {
+            return RoomPage::default();
+        }
+
+        let limit = limit.min(MAX_PAGE_SIZE) as usize;
+        let last_room_id = self.data::<Data>().room_id;
+
+        let mut rooms: Vec<RoomListing> = Vec::new();
+        let mut room_id = start.max(0);
+        let mut scanned = 0;
+
+        // stop at a full page or after `MAX_PAGE_SCAN` ids, whichever comes first
+        while room_id < last_room_id && rooms.len() < limit && scanned < MAX_PAGE_SCAN {
+            match self.data::<Data>().room.get(&room_id) {
+                Some(value) => {
+                    // a filter only matches vacant and listed rooms
+                    let matches =
+                        filter.map_or(true, |filter| value.is_available() && value.matches(filter));
+                    if matches {
+                        rooms.push(self.room_listing(value))
+                    }
+                }
+                None => (),
+            }
+            room_id += 1;
+            scanned += 1;
+        }
+
+        let next = if room_id < last_room_id {
+            Some(room_id)
+        } else {
+            None
+        };
+
+        RoomPage { rooms, next }
+    }
+
+    default fn track_availability(
+        &mut self,
+        room_id: RoomId,
+        was_available: bool,
+        is_available: bool,
+    ) {
+        match (was_available, is_available) {
+            (false, true) => {
+                // append at the end of the index
+                let position = self.available_room_count();
+                self.data::<Data>()
+                    .available_rooms
+                    .insert(&position, &room_id);
+                self.data::<Data>()
+                    .available_room_index
+                    .insert(&room_id, &position);
+                self.data::<Data>()
+                    .available_room_count
+                    .set(&(position + 1));
+            }
+            (true, false) => {
+                // move the last room of the index into the freed position
+                let position = match self.data::<Data>().available_room_index.get(&room_id) {
+                    Some(value) => value,
+                    None => return,
+                };
+                let last_position = self.available_room_count() - 1;
+                if position != last_position {
+                    if let Some(last_room_id) =
+                        self.data::<Data>().available_rooms.get(&last_position)
+                    {
+                        self.data::<Data>()
+                            .available_rooms
+                            .insert(&position, &last_room_id);
+                        self.data::<Data>()
+                            .available_room_index
+                            .insert(&last_room_id, &position);
+                    }
+                }
+                self.data::<Data>().available_rooms.remove(&last_position);
+                self.data::<Data>().available_room_index.remove(&room_id);
+                self.data::<Data>().available_room_count.set(&last_position);
+            }
+            _ => (),
+        }
+    }
+
+    default fn index_landlord_room(&mut self, landlord: AccountId, room_id: RoomId, add: bool) {
+        let count = self
+            .data::<Data>()
+            .landlord_room_count
+            .get(&landlord)
+            .unwrap_or_default();
+
+        if add {
+            // append at the end of the rooms of the landlord
+            self.data::<Data>()
+                .landlord_rooms
+                .insert(&(landlord, count), &room_id);
+            self.data::<Data>()
+                .landlord_room_index
+                .insert(&room_id, &count);
+            self.data::<Data>()
+                .landlord_room_count
+                .insert(&landlord, &(count + 1));
+            return;
+        }
+
+        // move the last room of the landlord into the freed position
+        let position = match self.data::<Data>().landlord_room_index.get(&room_id) {
+            Some(value) => value,
+            None => return,
+        };
+        let last_position = count - 1;
+        if position != last_position {
+            if let Some(last_room_id) = self
+                .data::<Data>()
+                .landlord_rooms
+                .get(&(landlord, last_position))
+            {
+                self.data::<Data>()
+                    .landlord_rooms
+                    .insert(&(landlord, position), &last_room_id);
+                self.data::<Data>()
+                    .landlord_room_index
+                    .insert(&last_room_id, &position);
+            }
+        }
+        self.data::<Data>()
+            .landlord_rooms
+            .remove(&(landlord, last_position));
+        self.data::<Data>().landlord_room_index.remove(&room_id);
+        self.data::<Data>()
+            .landlord_room_count
+            .insert(&landlord, &last_position);
+    }
+
+    default fn index_tenant_room(&mut self, tenant: AccountId, room_id: RoomId, add: bool) {
+        let count = self
+            .data::<Data>()
+            .tenant_room_count
+            .get(&tenant)
+            .unwrap_or_default();
+
+        if add {
+            // append at the end of the rooms of the tenant
+            self.data::<Data>()
+                .tenant_rooms
+                .insert(&(tenant, count), &room_id);
+            self.data::<Data>()
+                .tenant_room_index
+                .insert(&room_id, &count);
+            self.data::<Data>()
+                .tenant_room_count
+                .insert(&tenant, &(count + 1));
+            return;
+        }
+
+        // move the last room of the tenant into the freed position
+        let position = match self.data::<Data>().tenant_room_index.get(&room_id) {
+            Some(value) => value,
+            None => return,
+        };
+        let last_position = count - 1;
+        if position != last_position {
+            if let Some(last_room_id) = self
+                .data::<Data>()
+                .tenant_rooms
+                .get(&(tenant, last_position))
+            {
+                self.data::<Data>()
+                    .tenant_rooms
+                    .insert(&(tenant, position), &last_room_id);
+                self.data::<Data>()
+                    .tenant_room_index
+                    .insert(&last_room_id, &position);
+            }
+        }
+        self.data::<Data>()
+            .tenant_rooms
+            .remove(&(tenant, last_position));
+        self.data::<Data>().tenant_room_index.remove(&room_id);
+        self.data::<Data>()
+            .tenant_room_count
+            .insert(&tenant, &last_position);
+    }
+
+    default fn landlord_room_count(&self, landlord: AccountId) -> u32 {
+        let count = self
+            .data::<Data>()
+            .landlord_room_count
+            .get(&landlord)
+            .unwrap_or_default();
+
+        // rooms of the hotel belong to the owner of the contract
+        if landlord == self.owner() {
+            return count
+                + self
+                    .data::<Data>()
+                    .landlord_room_count
+                    .get(&T::env().account_id())
+                    .unwrap_or_default();
+        }
+
+        count
+    }
+
+    default fn tenant_room_count(&self, tenant: AccountId) -> u32 {
+        self.data::<Data>()
+            .tenant_room_count
+            .get(&tenant)
+            .unwrap_or_default()
+    }
+
+    // get how many times did user rent room
+    fn get_room_rent_count(&self, user: AccountId) -> Option<i32> {
+        self.data::<Data>().room_rent_quantity.get(&user)
+    }
+
+    // get the first available rooms of the vacancy index
+    default fn get_available_room(&self) -> Vec<Room> {
+        let end = self.available_room_count().min(MAX_PAGE_SIZE);
+
+        // rooms come in the order of the vacancy index, which is reshuffled as rooms are
+        // rented and vacated, so the order is unspecified
+        (0..end)
+            .filter_map(|position| self.data::<Data>().available_rooms.get(&position))
+            .filter_map(|room_id| self.data::<Data>().room.get(&room_id))
+            .map(|room| self.resolve_landlord(room))
+            .collect()
+    }
+
+    default fn room_listing(&self, room: Room) -> RoomListing {
+        let metadata = self.data::<Data>().room_metadata.get(&room.room_id);
+
+        RoomListing {
+            room: self.resolve_landlord(room),
+            metadata,
+        }
+    }
+
+    default fn room_landlord(&self, room: &Room) -> AccountId {
+        room.landlord_of(T::env().account_id(), self.owner())
+    }
+
+    default fn resolve_landlord(&self, mut room: Room) -> Room {
+        room.landlord = self.room_landlord(&room);
+        room
+    }
+
+    default fn get_rooms_by_landlord(
+        &self,
+        landlord: AccountId,
+        start: u32,
+        limit: u32,
+    ) -> Vec<RoomListing> {
+        let end = start
+            .saturating_add(limit.min(MAX_PAGE_SIZE))
+            .min(self.landlord_room_count(landlord));
+
+        // the owner of the contract gets their own rooms first, then the rooms of the hotel
+        let own_count = self
+            .data::<Data>()
+            .landlord_room_count
+            .get(&landlord)
+            .unwrap_or_default();
+        let hotel = T::env().account_id();
+
+        (start..end)
+            .filter_map(|position| {
+                if position < own_count {
+                    self.data::<Data>()
+                        .landlord_rooms
+                        .get(&(landlord, position))
+                } else {
+                    self.data::<Data>()
+                        .landlord_rooms
+                        .get(&(hotel, position - own_count))
+                }
+            })
+            .filter_map(|room_id| self.data::<Data>().room.get(&room_id))
+            .map(|room| self.room_listing(room))
+            .collect()
+    }
+
+    default fn get_rooms_by_tenant(
+        &self,
+        tenant: AccountId,
+        start: u32,
+        limit: u32,
+    ) -> Vec<RoomListing> {
+        let end = start
+            .saturating_add(limit.min(MAX_PAGE_SIZE))
+            .min(self.tenant_room_count(tenant));
+
+        (start..end)
+            .filter_map(|position| self.data::<Data>().tenant_rooms.get(&(tenant, position)))
+            .filter_map(|room_id| self.data::<Data>().room.get(&room_id))
+            .map(|room| self.room_listing(room))
+            .collect()
+    }
+
+    // search the available rooms of the hotel
+    default fn search_rooms(&self, filter: RoomFilter, start: RoomId, limit: u32) -> RoomPage {
+        self.rooms_page(start, limit, Some(&filter))
+    }
+
+    #[modifiers(non_reentrant)]
+    default fn withdraw(&mut self, amount: Balance) -> Result<(), HotelError> {
+        let caller = T::env().caller();
+
+        ensure!(amount > 0, HotelError::InvalidWithdrawAmount);
+
+        // check the caller has enough claimable balance
+        let claim = self.data::<Data>().claims.get(&caller).unwrap_or_default();
+        ensure!(claim >= amount, HotelError::InsufficientClaimBalance);
+
+        // reduce the claim before the value leaves the contract
+        self.data::<Data>()
+            .claims
+            .insert(&caller, &(claim - amount));
+        self.track_claim(caller, claim, claim - amount);
+
+        T::env()
+            .transfer(caller, amount)
+            .map_err(|_| HotelError::TransferFailed)?;
+
+        self.emit_withdrawal_event(caller, amount, Currency::Native);
+
+        Ok(())
+    }
+
+    #[modifiers(non_reentrant)]
+    default fn withdraw_token(
+        &mut self,
+        token: AccountId,
+        amount: Balance,
+    ) -> Result<(), HotelError> {
+        let caller = T::env().caller();
+
+        ensure!(amount > 0, HotelError::InvalidWithdrawAmount);
+
+        // check the caller has enough claimable balance in `token`
+        let claim = self.balance_of_token_claims(token, caller);
+        ensure!(claim >= amount, HotelError::InsufficientClaimBalance);
+
+        // reduce the claim before the tokens leave the contract
+        self.data::<Data>()
+            .token_claims
+            .insert(&(token, caller), &(claim - amount));
+
+        PSP22Ref::transfer(&token, caller, amount, Vec::new())?;
+
+        self.emit_withdrawal_event(caller, amount, Currency::Psp22(token));
+
+        Ok(())
+    }
+
+    // get the claimable balance of `account`
+    default fn balance_of_claims(&self, account: AccountId) -> Balance {
+        self.data::<Data>().claims.get(&account).unwrap_or_default()
+    }
+
+    // get the claimable balance of `account` in `token`
+    default fn balance_of_token_claims(&self, token: AccountId, account: AccountId) -> Balance {
+        self.data::<Data>()
+            .token_claims
+            .get(&(token, account))
+            .unwrap_or_default()
+    }
+
+    #[modifiers(non_reentrant, only_owner)]
+    default fn set_accepted_token(&mut self, token: Option<AccountId>) -> Result<(), HotelError> {
+        self.data::<Data>().accepted_token = token;
+        Ok(())
+    }
+
+    default fn get_accepted_token(&self) -> Option<AccountId> {
+        self.data::<Data>().accepted_token
+    }
+
+    default fn mint_lease(&mut self, agreement: &RoomAgreement) -> Result<(), HotelError> {
+        let id = Id::U32(agreement.agreement_id as u32);
+
+        psp34::Internal::_mint_to(self, agreement.tenant, id.clone())?;
+
+        // room id and terms of the agreement are readable by other dApps
+        let attributes = [
+            ("room_id", format!("{}", agreement.room_id)),
+            ("rent_per_month", format!("{}", agreement.rent_per_month)),
+            (
+                "security_deposit",
+                format!("{}", agreement.security_deposit),
+            ),
+            ("start_time", format!("{}", agreement.start_time)),
+            ("end_time", format!("{}", agreement.end_time)),
+            ("lock_in_period", format!("{}", agreement.lock_in_period)),
+        ];
+        for (key, value) in attributes {
+            metadata::Internal::_set_attribute(
+                self,
+                id.clone(),
+                key.as_bytes().to_vec(),
+                value.into_bytes(),
+            );
+        }
+
+        Ok(())
+    }
+
+    default fn burn_lease(&mut self, agreement_id: AgreementId) -> Result<(), HotelError> {
+        let id = Id::U32(agreement_id as u32);
+
+        // lease token can't be transferred, it is burned from the tenant it was minted to
+        if let Some(owner) = psp34::PSP34::owner_of(self, id.clone()) {
+            psp34::Internal::_burn_from(self, owner, id)?;
+        }
+
+        Ok(())
+    }
+
+    default fn pull_token(
+        &mut self,
+        token: AccountId,
+        from: AccountId,
+        amount: Balance,
+    ) -> Result<(), HotelError> {
+        // reentry is denied by the runtime, the token can't call back into the contract
+        PSP22Ref::transfer_from(&token, from, T::env().account_id(), amount, Vec::new())?;
+
+        Ok(())
+    }
+
+    default fn accepted_token(&self) -> Result<AccountId, HotelError> {
+        match self.data::<Data>().accepted_token {
+            Some(token) => Ok(token),
+            None => Err(HotelError::TokenNotAccepted),
+        }
+    }
+
+    default fn get_tenant_credit(&self, account: AccountId) -> Balance {
+        self.data::<Data>()
+            .tenant_credit
+            .get(&account)
+            .unwrap_or_default()
+    }
+
+    #[modifiers(non_reentrant)]
+    default fn claim_tenant_credit(&mut self) -> Result<Balance, HotelError> {
+        let caller = T::env().caller();
+
+        let credit = self.get_tenant_credit(caller);
+        ensure!(credit > 0, HotelError::NoTenantCredit);
+
+        self.data::<Data>().tenant_credit.remove(&caller);
+        self.track_tenant_credit(caller, credit, 0);
+        self.credit_claim(caller, credit, Currency::Native);
+
+        Ok(credit)
+    }
+
+    default fn add_tenant_credit(&mut self, account: AccountId, amount: Balance) {
+        if amount == 0 {
+            return;
+        }
+
+        let credit = self.get_tenant_credit(account);
+        self.data::<Data>()
+            .tenant_credit
+            .insert(&account, &(credit + amount));
+        self.track_tenant_credit(account, credit, credit + amount);
+    }
+
+    default fn credit_claim(&mut self, account: AccountId, amount: Balance, currency: Currency) {
+        if amount == 0 {
+            return;
+        }
+
+        match currency {
+            Currency::Native => {
+                let claim = self.balance_of_claims(account);
+                self.data::<Data>()
+                    .claims
+                    .insert(&account, &(claim + amount));
+                self.track_claim(account, claim, claim + amount);
+            }
+            Currency::Psp22(token) => {
+                let claim = self.balance_of_token_claims(token, account);
+                self.data::<Data>()
+                    .token_claims
+                    .insert(&(token, account), &(claim + amount));
+            }
+        }
+
+        self.emit_claim_credited_event(account, amount, currency);
+    }
+
+    default fn hold_deposit(
+        &mut self,
+        agreement_id: AgreementId,
+        amount: Balance,
+        currency: Currency,
+    ) {
+        let held = self.get_agreement_deposit(agreement_id);
+        self.data::<Data>()
+            .deposits
+            .insert(&agreement_id, &(held + amount));
+
+        match currency {
+            Currency::Native => self.data::<Data>().total_escrow += amount,
+            Currency::Psp22(token) => {
+                let escrow = self.get_token_escrow(token);
+                self.data::<Data>()
+                    .token_escrow
+                    .insert(&token, &(escrow + amount));
+            }
+        }
+    }
+
+    default fn release_deposit(
+        &mut self,
+        agreement_id: AgreementId,
+        amount: Balance,
+        account: AccountId,
+    ) {
+        let held = self.get_agreement_deposit(agreement_id);
+        let amount = amount.min(held);
+
+        self.data::<Data>()
+            .deposits
+            .insert(&agreement_id, &(held - amount));
+
+        // deposit is released in the currency it was paid in
+        let currency = self
+            .get_agreement(agreement_id)
+            .map(|agreement| agreement.currency)
+            .unwrap_or_default();
+
+        match currency {
+            Currency::Native => self.data::<Data>().total_escrow -= amount,
+            Currency::Psp22(token) => {
+                let escrow = self.get_token_escrow(token);
+                self.data::<Data>()
+                    .token_escrow
+                    .insert(&token, &(escrow - amount));
+            }
+        }
+
+        self.credit_claim(account, amount, currency);
+    }
+
+    #[modifiers(non_reentrant, only_owner)]
+    default fn pause(&mut self) -> Result<(), HotelError> {
+        pausable::Internal::_pause(self)
+    }
+
+    #[modifiers(non_reentrant, only_owner)]
+    default fn unpause(&mut self) -> Result<(), HotelError> {
+        pausable::Internal::_unpause(self)
+    }
+
+    fn get_landlord(&self) -> AccountId {
+        self.owner()
+    }
+
+    #[modifiers(non_reentrant, only_owner)]
+    default fn propose_new_landlord(&mut self, new_landlord: AccountId) -> Result<(), HotelError> {
+        ensure!(
+            new_landlord != ZERO_ADDRESS.into(),
+            HotelError::InvalidLandlordAddress
+        );
+
+        self.data::<Data>().pending_landlord = Some(new_landlord);
+
+        self.emit_landlord_proposed_event(self.owner(), new_landlord);
+
+        Ok(())
+    }
+
+    #[modifiers(non_reentrant)]
+    default fn accept_landlordship(&mut self) -> Result<(), HotelError> {
+        let caller = T::env().caller();
+        ensure!(
+            self.data::<Data>().pending_landlord == Some(caller),
+            HotelError::CallerIsNotPendingLandlord
+        );
+
+        let previous_landlord = self.owner();
+
+        // ownership is the only record of the contract `landlord`, rooms of the hotel
+        // follow it
+        self.data::<ownable::Data>().owner = caller;
+        self.data::<Data>().pending_landlord = None;
+
+        // role admin and staff roles of the previous owner move with the ownership
+        for role in [
+            access_control::DEFAULT_ADMIN_ROLE,
+            MANAGER,
+            ACCOUNTANT,
+            FRONT_DESK,
+            ATTESTER,
+        ] {
+            if self.has_role(role, previous_landlord) {
+                access_control::Internal::_setup_role(self, role, caller);
+                access_control::Internal::_do_revoke_role(self, role, previous_landlord);
+            }
+        }
+
+        ownable::Internal::_emit_ownership_transferred_event(
+            self,
+            Some(previous_landlord),
+            Some(caller),
+        );
+        self.emit_landlordship_transferred_event(previous_landlord, caller);
+
+        Ok(())
+    }
+
+    default fn get_pending_landlord(&self) -> Option<AccountId> {
+        self.data::<Data>().pending_landlord
+    }
+
+    #[modifiers(non_reentrant, only_owner)]
+    default fn register_landlord(&mut self, landlord: AccountId) -> Result<(), HotelError> {
+        self.data::<Data>().landlords.insert(&landlord, &true);
+        self.emit_landlord_registered_event(landlord);
+        Ok(())
+    }
+
+    #[modifiers(non_reentrant, only_owner)]
+    default fn remove_landlord(&mut self, landlord: AccountId) -> Result<(), HotelError> {
+        self.data::<Data>().landlords.remove(&landlord);
+        self.emit_landlord_removed_event(landlord);
+        Ok(())
+    }
+
+    default fn is_landlord(&self, account: AccountId) -> bool {
+        self.data::<Data>()
+            .landlords
+            .get(&account)
+            .unwrap_or_default()
+    }
+
+    #[modifiers(non_reentrant, only_hotel_role(ATTESTER))]
+    default fn attest_tenant(
+        &mut self,
+        account: AccountId,
+        status: VerificationStatus,
+        expiry: Timestamp,
+        document_hash: [u8; 32],
+    ) -> Result<(), HotelError> {
+        let now = T::env().block_timestamp();
+
+        // a verification must not be expired when it is recorded
+        if status == VerificationStatus::Verified {
+            ensure!(expiry > now, HotelError::InvalidVerificationExpiry);
+        }
+
+        let verification = Verification {
+            status,
+            expiry,
+            document_hash,
+            attester: T::env().caller(),
+            time_stamp: now,
+        };
+
+        self.data::<Data>()
+            .verifications
+            .insert(&account, &verification);
+
+        self.emit_tenant_attested_event(account, status, expiry);
+
+        Ok(())
+    }
+
+    default fn get_verification(&self, account: AccountId) -> Option<Verification> {
+        self.data::<Data>().verifications.get(&account)
+    }
+
+    default fn is_verified(&self, account: AccountId) -> bool {
+        match self.get_verification(account) {
+            Some(verification) => verification.is_valid(T::env().block_timestamp()),
+            None => false,
+        }
+    }
+
+    #[modifiers(non_reentrant)]
+    default fn unban(&mut self, account: AccountId) -> Result<(), HotelError> {
+        let ban = match self.get_ban(account) {
+            Some(value) => value,
+            None => return Err(HotelError::TenantNotBanned),
+        };
+
+        let caller = T::env().caller();
+        if caller != ban.banned_by {
+            ensure!(
+                self.has_role(FRONT_DESK, caller),
+                HotelError::MissingFrontDeskRole
+            );
+        }
+
+        self.data::<Data>().bans.remove(&account);
+
+        self.emit_tenant_unbanned_event(account);
+
+        Ok(())
+    }
+
+    default fn get_ban(&self, account: AccountId) -> Option<Ban> {
+        self.data::<Data>().bans.get(&account)
+    }
+
+    #[modifiers(non_reentrant, only_owner)]
+    default fn set_multisig(
+        &mut self,
+        signers: Vec<AccountId>,
+        threshold: u32,
+    ) -> Result<(), HotelError> {
+        ensure!(
+            threshold > 0 && threshold as usize <= signers.len(),
+            HotelError::InvalidMultisigThreshold
+        );
+
+        // every signer can only approve once, so duplicates would make the threshold unreachable
+        for (index, signer) in signers.iter().enumerate() {
+            ensure!(
+                !signers[..index].contains(signer),
+                HotelError::InvalidMultisigSigners
+            );
+        }
+
+        self.data::<Data>().signers = signers;
+        self.data::<Data>().threshold = threshold;
+
+        Ok(())
+    }
+
+    default fn get_signers(&self) -> Vec<AccountId> {
+        self.data::<Data>().signers.clone()
+    }
+
+    default fn get_threshold(&self) -> u32 {
+        self.data::<Data>().threshold
+    }
+
+    #[modifiers(non_reentrant, only_signer)]
+    default fn create_proposal(
+        &mut self,
+        action: ProposalAction,
+        deadline: Timestamp,
+    ) -> Result<ProposalId, HotelError> {
+        let caller = T::env().caller();
+        let now = T::env().block_timestamp();
+
+        ensure!(deadline > now, HotelError::InvalidProposalDeadline);
+        self.check_proposal_action(&action)?;
+
+        let proposal_id = self.next_proposal_id();
+
+        let proposal = Proposal {
+            proposal_id,
+            action,
+            proposer: caller,
+            approvals: Vec::new(),
+            deadline,
+            status: ProposalStatus::Pending,
+            time_stamp: now,
+        };
+
+        self.data::<Data>()
+            .proposals
+            .insert(&proposal_id, &proposal);
+
+        self.emit_proposal_created_event(proposal_id, caller);
+
+        // proposer approves their own proposal
+        self.add_approval(proposal_id)?;
+
+        Ok(proposal_id)
+    }
+
+    #[modifiers(non_reentrant, only_signer)]
+    default fn approve_proposal(&mut self, proposal_id: ProposalId) -> Result<(), HotelError> {
+        self.add_approval(proposal_id)
+    }
+
+    default fn add_approval(&mut self, proposal_id: ProposalId) -> Result<(), HotelError> {
+        let caller = T::env().caller();
+
+        let mut proposal = match self.data::<Data>().proposals.get(&proposal_id) {
+            Some(value) => value,
+            None => return Err(HotelError::ProposalNotFound),
+        };
+
+        ensure!(
+            proposal.status == ProposalStatus::Pending,
+            HotelError::ProposalNotPending
+        );
+        ensure!(
+            T::env().block_timestamp() <= proposal.deadline,
+            HotelError::ProposalExpired
+        );
+        ensure!(
+            !proposal.approvals.contains(&caller),
+            HotelError::ProposalAlreadyApproved
+        );
+
+        proposal.approvals.push(caller);
+
+        self.emit_proposal_approved_event(proposal_id, caller);
+
+        // action runs with the approval that meets the threshold
+        if proposal.approvals.len() >= self.data::<Data>().threshold as usize {
+            proposal.status = ProposalStatus::Executed;
+            self.execute_proposal(proposal.action.clone())?;
+            self.emit_proposal_executed_event(proposal_id);
+        }
+
+        self.data::<Data>()
+            .proposals
+            .insert(&proposal_id, &proposal);
+
+        Ok(())
+    }
+
+    default fn get_proposal(&self, proposal_id: ProposalId) -> Option<Proposal> {
+        let mut proposal = self.data::<Data>().proposals.get(&proposal_id)?;
+
+        // pending proposal past its deadline can no longer be approved
+        if proposal.status == ProposalStatus::Pending
+            && T::env().block_timestamp() > proposal.deadline
+        {
+            proposal.status = ProposalStatus::Expired;
+        }
+
+        Some(proposal)
+    }
+
+    default fn get_proposals(&self, start: ProposalId, limit: u32) -> ProposalPage {
+        // an empty page can't move the cursor, there is no next page to point at
+        if limit == 0 {
+            return ProposalPage::default();
+        }
+
+        let limit = limit.min(MAX_PAGE_SIZE) as usize;
+        let last_proposal_id = self.data::<Data>().proposal_id;
+
+        let mut proposals: Vec<Proposal> = Vec::new();
+        let mut proposal_id = start.max(0);
+        let mut scanned = 0;
+
+        // stop at a full page or after `MAX_PAGE_SCAN` ids, whichever comes first
+        while proposal_id < last_proposal_id && proposals.len() < limit && scanned < MAX_PAGE_SCAN {
+            match self.get_proposal(proposal_id) {
+                Some(value) => proposals.push(value),
+                None => (),
+            }
+            proposal_id += 1;
+            scanned += 1;
+        }
+
+        let next = if proposal_id < last_proposal_id {
+            Some(proposal_id)
+        } else {
+            None
+        };
+
+        ProposalPage { proposals, next }
+    }
+
+    default fn check_proposal_action(&self, action: &ProposalAction) -> Result<(), HotelError> {
+        let (room_id, agreement_id) = match *action {
+            ProposalAction::TerminateAgreement {
+                room_id,
+                agreement_id,
+            } => (room_id, Some(agreement_id)),
+            ProposalAction::ForfeitDeposit {
+                room_id,
+                agreement_id,
+                ..
+            } => (room_id, Some(agreement_id)),
+            ProposalAction::ChangeRent { room_id, .. } => (room_id, None),
+        };
+
+        let room = match self.data::<Data>().room.get(&room_id) {
+            Some(value) => value,
+            None => return Err(HotelError::RoomNotFound),
+        };
+
+        // signers act for the hotel, rooms of registered landlords are not theirs
+        ensure!(
+            room.landlord == T::env().account_id(),
+            HotelError::NotAHotelRoom
+        );
+
+        // agreement must still be the current one when the proposal runs
+        if let Some(agreement_id) = agreement_id {
+            ensure!(
+                room.vacant == false && room.agreement_id == agreement_id,
+                HotelError::StaleProposal
+            );
+        }
+
+        Ok(())
+    }
+
+    default fn execute_proposal(&mut self, action: ProposalAction) -> Result<(), HotelError> {
+        self.check_proposal_action(&action)?;
+
+        match action {
+            ProposalAction::TerminateAgreement { room_id, .. } => {
+                self.terminate_agreement(room_id, None)?;
+            }
+            ProposalAction::ForfeitDeposit {
+                room_id, amount, ..
+            } => {
+                self.forfeit_deposit(room_id, amount)?;
+            }
+            ProposalAction::ChangeRent {
+                room_id,
+                rent_per_month,
+                security_deposit,
+            } => {
+                self.change_rent(room_id, rent_per_month, security_deposit)?;
+            }
+        }
+
+        Ok(())
+    }
+
+    default fn change_rent(
+        &mut self,
+        room_id: RoomId,
+        rent_per_month: Balance,
+        security_deposit: Balance,
+    ) -> RoomResult {
+        // approved price changes give tenants the same notice as any other price change
+        let effective_at = T::env().block_timestamp() + self.data::<Data>().notice_period;
+
+        self.schedule_change(room_id, rent_per_month, security_deposit, effective_at)
+    }
+
+    #[modifiers(
+        non_reentrant,
+        only_room_landlord_or_role(room_id, MANAGER),
+        without_multisig(room_id)
+    )]
+    default fn schedule_price_change(
+        &mut self,
+        room_id: RoomId,
+        rent_per_month: Balance,
+        security_deposit: Balance,
+        effective_at: Timestamp,
+    ) -> RoomResult {
+        self.schedule_change(room_id, rent_per_month, security_deposit, effective_at)
+    }
+
+    default fn schedule_change(
+        &mut self,
+        room_id: RoomId,
+        rent_per_month: Balance,
+        security_deposit: Balance,
+        effective_at: Timestamp,
+    ) -> RoomResult {
+        ensure!(
+            self.data::<Data>().room.contains(&room_id),
+            HotelError::RoomNotFound
+        );
+        ensure!(rent_per_month > 0, HotelError::InvalidRentPerMonth);
+        ensure!(security_deposit > 0, HotelError::InvalidSecurityDeposit);
+
+        // tenants must be told at least `notice_period` in advance
+        let now = T::env().block_timestamp();
+        ensure!(
+            effective_at >= now + self.data::<Data>().notice_period,
+            HotelError::NoticePeriodTooShort
+        );
+
+        let change = PriceChange {
+            rent_per_month,
+            security_deposit,
+            effective_at,
+            scheduled_by: T::env().caller(),
+            time_stamp: now,
+        };
+
+        self.data::<Data>().price_changes.insert(&room_id, &change);
+
+        self.emit_rent_change_scheduled_event(
+            room_id,
+            rent_per_month,
+            security_deposit,
+            effective_at,
+        );
+
+        Ok(room_id)
+    }
+
+    #[modifiers(non_reentrant, only_room_landlord_or_role(room_id, MANAGER))]
+    default fn cancel_price_change(&mut self, room_id: RoomId) -> RoomResult {
+        ensure!(
+            self.data::<Data>().price_changes.contains(&room_id),
+            HotelError::PriceChangeNotFound
+        );
+
+        self.data::<Data>().price_changes.remove(&room_id);
+
+        Ok(room_id)
+    }
+
+    default fn get_price_change(&self, room_id: RoomId) -> Option<PriceChange> {
+        self.data::<Data>().price_changes.get(&room_id)
+    }
+
+    #[modifiers(non_reentrant, only_owner)]
+    default fn set_notice_period(&mut self, notice_period: Timestamp) -> Result<(), HotelError> {
+        // tenants are never given less notice than the default
+        ensure!(
+            notice_period >= DEFAULT_NOTICE_PERIOD,
+            HotelError::NoticePeriodTooShort
+        );
+
+        self.data::<Data>().notice_period = notice_period;
+        Ok(())
+    }
+
+    default fn get_notice_period(&self) -> Timestamp {
+        self.data::<Data>().notice_period
+    }
+
+    default fn pending_change_due(&self, room: &Room) -> Option<PriceChange> {
+        let change = self.get_price_change(room.room_id)?;
+
+        // billing cycle of an occupied room starts at its due date,
+        // a vacant room starts one with the next agreement
+        let cycle_start = if room.vacant {
+            T::env().block_timestamp()
+        } else {
+            room.next_rent_due_date
+        };
+
+        if change.effective_at <= cycle_start {
+            Some(change)
+        } else {
+            None
+        }
+    }
+
+    default fn apply_price_change(&mut self, room_id: RoomId) -> Result<Room, HotelError> {
+        let mut room = match self.data::<Data>().room.get(&room_id) {
+            Some(value) => value,
+            None => return Err(HotelError::RoomNotFound),
+        };
+
+        if let Some(change) = self.pending_change_due(&room) {
+            room.rent_per_month = change.rent_per_month;
+            room.security_deposit = change.security_deposit;
+
+            self.data::<Data>().room.insert(&room_id, &room);
+            self.data::<Data>().price_changes.remove(&room_id);
+        }
+
+        Ok(room)
+    }
+
+    default fn total_claims(&self) -> Balance {
+        self.data::<Data>().total_claims.get().unwrap_or_default()
+    }
+
+    default fn total_tenant_credit(&self) -> Balance {
+        self.data::<Data>()
+            .total_tenant_credit
+            .get()
+            .unwrap_or_default()
+    }
+
+    default fn tracks_totals(&self, account: AccountId) -> bool {
+        // while `migrate` runs, an account joins the totals once it is counted
+        self.data::<Data>().storage_version == STORAGE_VERSION
+            || self.data::<Data>().totals_counted.get(&account) == Some(STORAGE_VERSION)
+    }
+
+    default fn track_claim(&mut self, account: AccountId, previous: Balance, current: Balance) {
+        if !self.tracks_totals(account) {
+            return;
+        }
+
+        let total_claims = self.total_claims() - previous + current;
+        self.data::<Data>().total_claims.set(&total_claims);
+    }
+
+    default fn track_tenant_credit(
+        &mut self,
+        account: AccountId,
+        previous: Balance,
+        current: Balance,
+    ) {
+        if !self.tracks_totals(account) {
+            return;
+        }
+
+        let total_tenant_credit = self.total_tenant_credit() - previous + current;
+        self.data::<Data>()
+            .total_tenant_credit
+            .set(&total_tenant_credit);
+    }
+
+    default fn count_totals(&mut self, account: AccountId) {
+        if self.tracks_totals(account) {
+            return;
+        }
+
+        let total_claims = self.total_claims() + self.balance_of_claims(account);
+        self.data::<Data>().total_claims.set(&total_claims);
+        let total_tenant_credit = self.total_tenant_credit() + self.get_tenant_credit(account);
+        self.data::<Data>()
+            .total_tenant_credit
+            .set(&total_tenant_credit);
+
+        self.data::<Data>()
+            .totals_counted
+            .insert(&account, &STORAGE_VERSION);
+    }
+
+    default fn get_reconciliation(&self) -> Reconciliation {
+        let contract_balance = T::env().balance();
+        let held_deposits = self.data::<Data>().total_escrow;
+        let tenant_credits = self.total_tenant_credit();
+        let claims = self.total_claims();
+
+        let liabilities = held_deposits + tenant_credits + claims;
+
+        Reconciliation {
+            contract_balance,
+            held_deposits,
+            tenant_credits,
+            claims,
+            liabilities,
+            surplus: contract_balance.saturating_sub(liabilities),
+        }
+    }
+
+    #[modifiers(non_reentrant, only_owner)]
+    default fn withdraw_surplus(&mut self, amount: Balance) -> Result<(), HotelError> {
+        let caller = T::env().caller();
+
+        ensure!(amount > 0, HotelError::InvalidWithdrawAmount);
+
+        // totals only cover every claim once `migrate` has counted all of them
+        ensure!(
+            self.data::<Data>().storage_version == STORAGE_VERSION,
+            HotelError::MigrationPending
+        );
+
+        // only value nobody has a claim on can leave the contract
+        let reconciliation = self.get_reconciliation();
+        ensure!(
+            amount <= reconciliation.surplus,
+            HotelError::InsufficientSurplus
+        );
+
+        T::env()
+            .transfer(caller, amount)
+            .map_err(|_| HotelError::TransferFailed)?;
+
+        self.emit_surplus_withdrawn_event(
+            caller,
+            amount,
+            reconciliation.contract_balance,
+            reconciliation.liabilities,
+        );
+
+        Ok(())
+    }
+
+    #[modifiers(non_reentrant, only_owner)]
+    default fn upgrade(&mut self, code_hash: [u8; 32]) -> Result<(), HotelError> {
+        ink::env::set_code_hash(&code_hash).map_err(|_| HotelError::UpgradeFailed)?;
+
+        self.emit_contract_upgraded_event(code_hash);
+
+        Ok(())
+    }
+
+    #[modifiers(non_reentrant, only_owner)]
+    default fn migrate(&mut self, start: i32, limit: u32) -> Result<Option<i32>, HotelError> {
+        let from_version = self.data::<Data>().storage_version;
+        ensure!(from_version < STORAGE_VERSION, HotelError::AlreadyMigrated);
+
+        // builds of version 0 had no `upgrade`, so their storage never reaches this code
+        ensure!(from_version >= 1, HotelError::UnsupportedStorageVersion);
+
+        // batches follow each other, a repeated or skipped batch is rejected
+        let next_id = self
+            .data::<Data>()
+            .migration_next_id
+            .get()
+            .unwrap_or_default();
+        ensure!(
+            limit > 0 && start == next_id,
+            HotelError::InvalidMigrationBatch
+        );
+
+        // totals are rebuilt from the claims and tenant credits of the parties of every
+        // agreement and rent, earlier versions may not have tracked them
+        if start == 0 {
+            self.data::<Data>().total_claims.set(&0);
+            self.data::<Data>().total_tenant_credit.set(&0);
+        }
+
+        // rooms, agreements, rents and proposals share the id range of a batch
+        let last_id = self
+            .data::<Data>()
+            .room_id
+            .max(self.data::<Data>().agreement_id)
+            .max(self.data::<Data>().rent_id)
+            .max(self.data::<Data>().proposal_id);
+        let end = start
+            .saturating_add(limit.min(i32::MAX as u32) as i32)
+            .min(last_id);
+
+        for id in start..end {
+            self.migrate_room(id, from_version);
+            self.migrate_agreement(id, from_version);
+            self.migrate_rent(id);
+            self.migrate_proposal(id, from_version);
+        }
+
+        if end < last_id {
+            self.data::<Data>().migration_next_id.set(&end);
+            return Ok(Some(end));
+        }
+
+        // rent of hotel rooms is paid to whoever owns the contract now
+        let owner = self.owner();
+        self.count_totals(owner);
+
+        self.data::<Data>().migration_next_id.set(&0);
+        self.data::<Data>().storage_version = STORAGE_VERSION;
+
+        self.emit_storage_migrated_event(from_version, STORAGE_VERSION);
+
+        Ok(None)
+    }
+
+    default fn old_room(&self, room_id: RoomId, from_version: u32) -> Option<Room> {
+        // records of an older version can't be read through the mappings anymore,
+        // they are read with the old layout from the mapping key
+        let room_key = self.data::<Data>().room.key();
+        let mut room = match from_version {
+            1 => read_raw::<_, RoomV1>(room_key, &room_id)?.migrate(),
+            _ => return None,
+        };
+
+        // rooms stored by the owner become rooms of the hotel
+        if room.landlord == self.owner() {
+            room.landlord = T::env().account_id();
+        }
+
+        Some(room)
+    }
+
+    default fn migrate_room(&mut self, room_id: RoomId, from_version: u32) {
+        let room = match self.old_room(room_id, from_version) {
+            Some(value) => value,
+            None => return,
+        };
+        self.data::<Data>().room.insert(&room_id, &room);
+
+        // version 1 kept no room counts or indexes
+        let room_count = self.room_count();
+        self.data::<Data>().room_count.set(&(room_count + 1));
+        self.track_availability(room_id, false, room.is_available());
+        self.index_landlord_room(room.landlord, room_id, true);
+        if !room.vacant {
+            self.index_tenant_room(room.current_tenant, room_id, true);
+        }
+    }
+
+    default fn migrate_agreement(&mut self, agreement_id: AgreementId, from_version: u32) {
+        if from_version == 1 {
+            self.convert_agreement(agreement_id);
+        }
+
+        if let Some(agreement) = self.data::<Data>().agreement.get(&agreement_id) {
+            self.count_totals(agreement.tenant);
+            self.count_totals(agreement.landlord);
+        }
+    }
+
+    default fn convert_agreement(&mut self, agreement_id: AgreementId) {
+        let agreement_key = self.data::<Data>().agreement.key();
+        let agreement = match read_raw::<_, RoomAgreementV1>(agreement_key, &agreement_id) {
+            Some(value) => value,
+            None => return,
+        };
+
+        // terms of the room applied to its agreements in version 1, the room may not be
+        // converted yet
+        let (billing_period, late_fee_policy) = self
+            .old_room(agreement.room_id, 1)
+            .or_else(|| self.data::<Data>().room.get(&agreement.room_id))
+            .map_or((DEFAULT_BILLING_PERIOD, Default::default()), |room| {
+                (room.billing_period, room.late_fee_policy)
+            });
+
+        self.data::<Data>().agreement.insert(
+            &agreement_id,
+            &agreement.migrate(billing_period, late_fee_policy),
+        );
+    }
+
+    default fn migrate_rent(&mut self, rent_id: RentId) {
+        if let Some(rent) = self.data::<Data>().rent.get(&rent_id) {
+            self.count_totals(rent.tenant_address);
+            self.count_totals(rent.land_lord_address);
+        }
+    }
+
+    default fn migrate_proposal(&mut self, proposal_id: ProposalId, from_version: u32) {
+        if from_version != 1 {
+            return;
+        }
+
+        let proposal_key = self.data::<Data>().proposals.key();
+        let proposal = match read_raw::<_, ProposalV1>(proposal_key, &proposal_id) {
+            Some(value) => value,
+            None => return,
+        };
+
+        // the room may not be converted yet
+        let room_id = proposal.action.room_id();
+        let (agreement_id, security_deposit) = self
+            .old_room(room_id, 1)
+            .or_else(|| self.data::<Data>().room.get(&room_id))
+            .map_or((0, 0), |room| (room.agreement_id, room.security_deposit));
+
+        self.data::<Data>().proposals.insert(
+            &proposal_id,
+            &proposal.migrate(agreement_id, security_deposit),
+        );
+    }
+
+    default fn get_storage_version(&self) -> u32 {
+        self.data::<Data>().storage_version
+    }
+
+    default fn next_room_id(&mut self) -> RoomId {
+        let room_id = self.data::<Data>().room_id;
+        self.data::<Data>().room_id += 1;
+        room_id
+    }
+
+    default fn next_agreement_id(&mut self) -> AgreementId {
+        let agreement_id = self.data::<Data>().agreement_id;
+        self.data::<Data>().agreement_id += 1;
+        agreement_id
+    }
+
+    default fn next_rent_id(&mut self) -> RentId {
+        let rent_id = self.data::<Data>().rent_id;
+        self.data::<Data>().rent_id += 1;
+        rent_id
+    }
+
+    default fn next_proposal_id(&mut self) -> ProposalId {
+        let proposal_id = self.data::<Data>().proposal_id;
+        self.data::<Data>().proposal_id += 1;
+        proposal_id
+    }
+}
+
+impl<T> HotelRoomBookingEvents for T
 where
     T: Storage<Data>,
 {
     default fn emit_add_room_event(&self, _room_id: RoomId, _owner: AccountId) {}
-    default fn emit_sign_agreement_event(&self, _room_id: RoomId, _agreement_signer: AccountId) {}
-    default fn emit_rent_payment_event(&self, _room_id: RoomId, _rent_payment_signer: AccountId) {}
+    default fn emit_sign_agreement_event(
+        &self,
+        _room_id: RoomId,
+        _agreement_signer: AccountId,
+        _credited: Balance,
+    ) {
+    }
+    default fn emit_rent_payment_event(
+        &self,
+        _room_id: RoomId,
+        _rent_payment_signer: AccountId,
+        _credited: Balance,
+    ) {
+    }
     default fn emit_agreement_complete_event(&self, _room_id: RoomId) {}
     default fn emit_agreement_terminated_event(&self, _room_id: RoomId) {}
+    default fn emit_agreement_exited_event(
+        &self,
+        _room_id: RoomId,
+        _tenant: AccountId,
+        _penalty: Balance,
+    ) {
+    }
+    default fn emit_claim_credited_event(
+        &self,
+        _account: AccountId,
+        _amount: Balance,
+        _currency: Currency,
+    ) {
+    }
+    default fn emit_withdrawal_event(
+        &self,
+        _account: AccountId,
+        _amount: Balance,
+        _currency: Currency,
+    ) {
+    }
+    default fn emit_deposit_deducted_event(&self, _room_id: RoomId, _amount: Balance) {}
+    default fn emit_landlord_registered_event(&self, _landlord: AccountId) {}
+    default fn emit_landlord_removed_event(&self, _landlord: AccountId) {}
+    default fn emit_tenant_attested_event(
+        &self,
+        _account: AccountId,
+        _status: VerificationStatus,
+        _expiry: Timestamp,
+    ) {
+    }
+    default fn emit_tenant_banned_event(
+        &self,
+        _account: AccountId,
+        _reason_code: u32,
+        _expiry: Option<Timestamp>,
+    ) {
+    }
+    default fn emit_tenant_unbanned_event(&self, _account: AccountId) {}
+    default fn emit_landlord_proposed_event(&self, _landlord: AccountId, _proposed: AccountId) {}
+    default fn emit_landlordship_transferred_event(&self, _previous: AccountId, _new: AccountId) {}
+    default fn emit_proposal_created_event(&self, _proposal_id: ProposalId, _proposer: AccountId) {}
+    default fn emit_proposal_approved_event(&self, _proposal_id: ProposalId, _signer: AccountId) {}
+    default fn emit_proposal_executed_event(&self, _proposal_id: ProposalId) {}
+    default fn emit_rent_change_scheduled_event(
+        &self,
+        _room_id: RoomId,
+        _rent_per_month: Balance,
+        _security_deposit: Balance,
+        _effective_at: Timestamp,
+    ) {
+    }
+    default fn emit_contract_upgraded_event(&self, _code_hash: [u8; 32]) {}
+    default fn emit_storage_migrated_event(&self, _from_version: u32, _to_version: u32) {}
+    default fn emit_surplus_withdrawn_event(
+        &self,
+        _account: AccountId,
+        _amount: Balance,
+        _contract_balance: Balance,
+        _liabilities: Balance,
+    ) {
+    }
+    default fn emit_room_updated_event(&self, _room_id: RoomId) {}
+    default fn emit_room_removed_event(&self, _room_id: RoomId) {}
+    default fn emit_billing_period_changed_event(
+        &self,
+        _room_id: RoomId,
+        _billing_period: Timestamp,
+    ) {
+    }
+    default fn emit_late_fee_policy_changed_event(&self, _room_id: RoomId, _policy: LateFeePolicy) {
+    }
+    default fn emit_lease_terms_changed_event(&self, _room_id: RoomId, _terms: LeaseTerms) {}
+    default fn emit_room_metadata_updated_event(
+        &self,
+        _room_id: RoomId,
+        _content: ContentId,
+        _mime_type: String,
+    ) {
+    }
+}
+
+// modifier to check normal user, landlord is not allowed to rent their own room
+#[modifier_definition]
+pub fn is_normal_user<T, F, R, E>(instance: &mut T, body: F, room_id: RoomId) -> Result<R, E>
+where
+    T: Storage<Data> + Storage<ownable::Data>,
+    F: FnOnce(&mut T) -> Result<R, E>,
+    E: From<HotelError>,
+{
+    let room = match instance.data::<Data>().room.get(&room_id) {
+        Some(value) => value,
+        None => return Err(From::from(HotelError::RoomNotFound)),
+    };
+    let landlord = room.landlord_of(
+        T::env().account_id(),
+        instance.data::<ownable::Data>().owner,
+    );
+    ensure!(
+        T::env().caller() != landlord,
+        HotelError::CallerIsRoomLandlord
+    );
+    body(instance)
+}
+
+// modifier to check caller holds a valid tenant verification
+#[modifier_definition]
+pub fn is_verified_tenant<T, F, R, E>(instance: &mut T, body: F) -> Result<R, E>
+where
+    T: Storage<Data>,
+    F: FnOnce(&mut T) -> Result<R, E>,
+    E: From<HotelError>,
+{
+    let is_verified = match instance.data().verifications.get(&T::env().caller()) {
+        Some(verification) => verification.is_valid(T::env().block_timestamp()),
+        None => false,
+    };
+    ensure!(is_verified, HotelError::TenantNotVerified);
+    body(instance)
+}
+
+// modifier to check caller is not banned from signing agreements
+#[modifier_definition]
+pub fn is_not_banned<T, F, R, E>(instance: &mut T, body: F) -> Result<R, E>
+where
+    T: Storage<Data>,
+    F: FnOnce(&mut T) -> Result<R, E>,
+    E: From<HotelError>,
+{
+    let is_banned = match instance.data().bans.get(&T::env().caller()) {
+        Some(ban) => ban.is_active(T::env().block_timestamp()),
+        None => false,
+    };
+    ensure!(!is_banned, HotelError::TenantBanned);
+    body(instance)
+}
+
+// modifier to check the action on a room of the hotel doesn't bypass the multisig, once
+// signers are set it only runs as an approved proposal
+#[modifier_definition]
+pub fn without_multisig<T, F, R, E>(instance: &mut T, body: F, room_id: RoomId) -> Result<R, E>
+where
+    T: Storage<Data>,
+    F: FnOnce(&mut T) -> Result<R, E>,
+    E: From<HotelError>,
+{
+    let room = match instance.data().room.get(&room_id) {
+        Some(value) => value,
+        None => return Err(From::from(HotelError::RoomNotFound)),
+    };
+    ensure!(
+        instance.data().signers.is_empty() || room.landlord != T::env().account_id(),
+        HotelError::ProposalRequired
+    );
+    body(instance)
 }
 
-// modifier to check normal user
+// modifier to check caller is one of the multisig signers
 #[modifier_definition]
-pub fn is_normal_user<T, F, R, E>(instance: &mut T, body: F) -> Result<R, E>
+pub fn only_signer<T, F, R, E>(instance: &mut T, body: F) -> Result<R, E>
 where
     T: Storage<Data>,
     F: FnOnce(&mut T) -> Result<R, E>,
     E: From<HotelError>,
 {
     ensure!(
-        T::env().caller() != instance.data().land_lord,
-        HotelError::CallerIsOwner
+        instance.data().signers.contains(&T::env().caller()),
+        HotelError::CallerIsNotSigner
+    );
+    body(instance)
+}
+
+// modifier to check new agreements and payments are not paused
+#[modifier_definition]
+pub fn not_paused<T, F, R, E>(instance: &mut T, body: F) -> Result<R, E>
+where
+    T: Storage<pausable::Data>,
+    F: FnOnce(&mut T) -> Result<R, E>,
+    E: From<HotelError>,
+{
+    ensure!(
+        !pausable::Internal::_paused(instance),
+        HotelError::ContractPaused
+    );
+    body(instance)
+}
+
+// modifier to check caller is a registered landlord or has `role`
+#[modifier_definition]
+pub fn is_landlord_or_role<T, F, R, E>(instance: &mut T, body: F, role: RoleType) -> Result<R, E>
+where
+    T: Storage<Data> + Storage<access_control::Data>,
+    F: FnOnce(&mut T) -> Result<R, E>,
+    E: From<HotelError>,
+{
+    let caller = T::env().caller();
+    let is_landlord = instance
+        .data::<Data>()
+        .landlords
+        .get(&caller)
+        .unwrap_or_default();
+    if !is_landlord {
+        ensure!(instance.has_role(role, caller), missing_role_error(role));
+    }
+    body(instance)
+}
+
+// modifier to check caller is the landlord of the room or, for rooms of the hotel, has
+// `role`, rooms of registered landlords are only managed by their landlord
+#[modifier_definition]
+pub fn only_room_landlord_or_role<T, F, R, E>(
+    instance: &mut T,
+    body: F,
+    room_id: RoomId,
+    role: RoleType,
+) -> Result<R, E>
+where
+    T: Storage<Data> + Storage<access_control::Data> + Storage<ownable::Data>,
+    F: FnOnce(&mut T) -> Result<R, E>,
+    E: From<HotelError>,
+{
+    let room = match instance.data::<Data>().room.get(&room_id) {
+        Some(value) => value,
+        None => return Err(From::from(HotelError::RoomNotFound)),
+    };
+    let caller = T::env().caller();
+    let hotel = T::env().account_id();
+    if caller != room.landlord_of(hotel, instance.data::<ownable::Data>().owner) {
+        ensure!(room.landlord == hotel, HotelError::CallerIsNotRoomLandlord);
+        ensure!(instance.has_role(role, caller), missing_role_error(role));
+    }
+    body(instance)
+}
+
+// modifier to check caller has `role` in the hotel staff
+#[modifier_definition]
+pub fn only_hotel_role<T, F, R, E>(instance: &mut T, body: F, role: RoleType) -> Result<R, E>
+where
+    T: Storage<access_control::Data>,
+    F: FnOnce(&mut T) -> Result<R, E>,
+    E: From<HotelError>,
+{
+    ensure!(
+        instance.has_role(role, T::env().caller()),
+        missing_role_error(role)
     );
     body(instance)
 }
+
+fn missing_role_error(role: RoleType) -> HotelError {
+    match role {
+        MANAGER => HotelError::MissingManagerRole,
+        ACCOUNTANT => HotelError::MissingAccountantRole,
+        FRONT_DESK => HotelError::MissingFrontDeskRole,
+        ATTESTER => HotelError::MissingAttesterRole,
+        _ => HotelError::AccessControlError(access_control::AccessControlError::MissingRole),
+    }
+}
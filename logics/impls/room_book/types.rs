@@ -1,18 +1,178 @@
-use ink::prelude::string::String;
+use ink::{
+    prelude::{string::String, vec::Vec},
+    storage::Lazy,
+};
 use openbrush::traits::Timestamp;
 use openbrush::{
-    contracts::ownable::OwnableError,
+    contracts::{
+        access_control::{AccessControlError, RoleType},
+        ownable::OwnableError,
+        pausable::PausableError,
+        psp22::PSP22Error,
+        psp34::PSP34Error,
+        reentrancy_guard::ReentrancyGuardError,
+    },
     storage::Mapping,
-    traits::{AccountId, ZERO_ADDRESS},
+    traits::{AccountId, Balance, ZERO_ADDRESS},
 };
 
 // type defination for `room_id`, `agreement_id`, `rent_id`
 pub type RoomId = i32;
 pub type AgreementId = i32;
 pub type RentId = i32;
+pub type ProposalId = i32;
 
 pub type RoomResult = Result<RoomId, HotelError>;
 
+// hotel staff roles, owner of the contract is the admin of every role
+pub const MANAGER: RoleType = ink::selector_id!("MANAGER");
+pub const ACCOUNTANT: RoleType = ink::selector_id!("ACCOUNTANT");
+pub const FRONT_DESK: RoleType = ink::selector_id!("FRONT_DESK");
+
+// records tenant verifications, only verified tenants can sign an agreement
+pub const ATTESTER: RoleType = ink::selector_id!("ATTESTER");
+
+// one day in `Timestamp` milliseconds
+pub const DAY: Timestamp = 24 * 60 * 60 * 1000;
+
+// billing period of a room unless the owner configures another one
+pub const DEFAULT_BILLING_PERIOD: Timestamp = 30 * DAY;
+
+// rent for the next billing period can be paid this long before it is due
+pub const RENT_PAYMENT_WINDOW: Timestamp = 7 * DAY;
+
+// lease duration and lock-in period of a room unless the owner configures other terms
+pub const DEFAULT_LEASE_DURATION: Timestamp = 12 * DEFAULT_BILLING_PERIOD;
+pub const DEFAULT_LOCK_IN_PERIOD: Timestamp = DEFAULT_BILLING_PERIOD;
+
+// rent and deposit changes take effect at least this long after they are scheduled
+// unless the owner configures a longer notice period
+pub const DEFAULT_NOTICE_PERIOD: Timestamp = DEFAULT_BILLING_PERIOD;
+
+// paged room queries return at most `MAX_PAGE_SIZE` rooms and look at
+// no more than `MAX_PAGE_SCAN` room ids per call
+pub const MAX_PAGE_SIZE: u32 = 50;
+pub const MAX_PAGE_SCAN: u32 = 200;
+
+// limits of the off-chain media metadata of a room
+pub const CID_V0_LENGTH: usize = 46;
+pub const MAX_CID_LENGTH: usize = 128;
+pub const MAX_MIME_TYPE_LENGTH: usize = 64;
+const BASE58_ALPHABET: &[u8] = b"123456789ABCDEFGHJKLMNPQRSTUVWXYZabcdefghijkmnopqrstuvwxyz";
+
+// late fee percentage is expressed in basis points of `rent_per_month`
+pub const BASIS_POINTS: u32 = 10_000;
+
+// amenities of a room as bitflags, a room has every amenity whose bit is set
+pub type Amenities = u32;
+
+pub const AMENITY_WIFI: Amenities = 1 << 0;
+pub const AMENITY_BALCONY: Amenities = 1 << 1;
+pub const AMENITY_PRIVATE_BATHROOM: Amenities = 1 << 2;
+pub const AMENITY_KITCHEN: Amenities = 1 << 3;
+pub const AMENITY_AIR_CONDITIONING: Amenities = 1 << 4;
+pub const AMENITY_WASHING_MACHINE: Amenities = 1 << 5;
+pub const AMENITY_PARKING: Amenities = 1 << 6;
+pub const AMENITY_FURNISHED: Amenities = 1 << 7;
+
+// currency an agreement, rent or claim is paid in
+#[derive(scale::Decode, scale::Encode, Default, Debug, Clone, Copy, Eq, PartialEq)]
+#[cfg_attr(
+    feature = "std",
+    derive(scale_info::TypeInfo, ink::storage::traits::StorageLayout)
+)]
+pub enum Currency {
+    #[default]
+    Native,
+    Psp22(AccountId),
+}
+
+#[derive(scale::Decode, scale::Encode, Default, Debug, Clone, Eq, PartialEq)]
+#[cfg_attr(
+    feature = "std",
+    derive(scale_info::TypeInfo, ink::storage::traits::StorageLayout)
+)]
+pub struct LateFeePolicy {
+    pub flat_fee_per_day: Balance,
+    // charged per day in basis points of `rent_per_month`
+    pub percentage_per_day: u32,
+    pub grace_period: Timestamp,
+}
+
+#[derive(scale::Decode, scale::Encode, Debug, Clone, Eq, PartialEq)]
+#[cfg_attr(
+    feature = "std",
+    derive(scale_info::TypeInfo, ink::storage::traits::StorageLayout)
+)]
+pub struct LeaseTerms {
+    pub lease_duration: Timestamp,
+    pub lock_in_period: Timestamp,
+    // taken from the deposit escrow when the tenant exits during the lock-in period
+    pub early_exit_penalty: Balance,
+}
+
+impl Default for LeaseTerms {
+    fn default() -> Self {
+        LeaseTerms {
+            lease_duration: DEFAULT_LEASE_DURATION,
+            lock_in_period: DEFAULT_LOCK_IN_PERIOD,
+            early_exit_penalty: Default::default(),
+        }
+    }
+}
+
+#[derive(scale::Decode, scale::Encode, Default, Debug, Clone, Copy, Eq, PartialEq)]
+#[cfg_attr(
+    feature = "std",
+    derive(scale_info::TypeInfo, ink::storage::traits::StorageLayout)
+)]
+pub enum RoomCategory {
+    #[default]
+    Single,
+    Double,
+    Twin,
+    Studio,
+    Suite,
+    Apartment,
+}
+
+#[derive(scale::Decode, scale::Encode, Debug, Clone, Eq, PartialEq)]
+#[cfg_attr(
+    feature = "std",
+    derive(scale_info::TypeInfo, ink::storage::traits::StorageLayout)
+)]
+pub struct RoomAttributes {
+    pub category: RoomCategory,
+    pub max_occupants: u32,
+    pub beds: u32,
+    // floor area in square metres
+    pub floor_area: u32,
+    pub amenities: Amenities,
+}
+
+impl Default for RoomAttributes {
+    fn default() -> Self {
+        RoomAttributes {
+            category: Default::default(),
+            max_occupants: 1,
+            beds: 1,
+            floor_area: Default::default(),
+            amenities: Default::default(),
+        }
+    }
+}
+
+// filter of `search_rooms`, `None` fields and unset amenity bits match every room
+#[derive(scale::Decode, scale::Encode, Default, Debug, Clone, Eq, PartialEq)]
+#[cfg_attr(feature = "std", derive(scale_info::TypeInfo))]
+pub struct RoomFilter {
+    pub category: Option<RoomCategory>,
+    pub min_rent: Option<Balance>,
+    pub max_rent: Option<Balance>,
+    pub min_occupants: Option<u32>,
+    pub amenities: Amenities,
+}
+
 // pub const ROOM_STORAGE_KEY: u32 = openbrush::storage_unique_key!(Room);
 
 #[derive(scale::Decode, scale::Encode, Debug, Clone, Eq, PartialEq)]
@@ -30,9 +190,16 @@ pub struct Room {
     pub security_deposit: u128,
     pub time_stamp: Timestamp,
     pub vacant: bool,
+    // rooms of the hotel are held by the contract account and belong to its owner
     pub landlord: AccountId,
     pub current_tenant: AccountId,
     pub next_rent_due_date: Timestamp,
+    pub billing_period: Timestamp,
+    pub late_fee_policy: LateFeePolicy,
+    pub lease_terms: LeaseTerms,
+    // delisted rooms take no new agreements and are hidden from `get_available_room`
+    pub listed: bool,
+    pub attributes: RoomAttributes,
 }
 
 impl Default for Room {
@@ -49,13 +216,214 @@ impl Default for Room {
             landlord: ZERO_ADDRESS.into(),
             current_tenant: ZERO_ADDRESS.into(),
             next_rent_due_date: Default::default(),
+            billing_period: DEFAULT_BILLING_PERIOD,
+            late_fee_policy: Default::default(),
+            lease_terms: Default::default(),
+            listed: Default::default(),
+            attributes: Default::default(),
+        }
+    }
+}
+
+impl Room {
+    // account the room belongs to, rooms held by the `hotel` contract follow its `owner`
+    pub fn landlord_of(&self, hotel: AccountId, owner: AccountId) -> AccountId {
+        if self.landlord == hotel {
+            owner
+        } else {
+            self.landlord
+        }
+    }
+
+    // room can be rented right now
+    pub fn is_available(&self) -> bool {
+        self.vacant && self.listed
+    }
+
+    // check whether the room has every attribute asked for by `filter`
+    pub fn matches(&self, filter: &RoomFilter) -> bool {
+        let attributes = &self.attributes;
+
+        filter
+            .category
+            .map_or(true, |category| attributes.category == category)
+            && filter
+                .min_rent
+                .map_or(true, |min_rent| self.rent_per_month >= min_rent)
+            && filter
+                .max_rent
+                .map_or(true, |max_rent| self.rent_per_month <= max_rent)
+            && filter
+                .min_occupants
+                .map_or(true, |occupants| attributes.max_occupants >= occupants)
+            && attributes.amenities & filter.amenities == filter.amenities
+    }
+
+    // late fee accrued at `now` under `policy` for the rent due at `next_rent_due_date`,
+    // every started day after the grace period is charged
+    pub fn late_fee(&self, policy: &LateFeePolicy, now: Timestamp) -> Balance {
+        let late_from = self.next_rent_due_date.saturating_add(policy.grace_period);
+
+        if now <= late_from {
+            return 0;
+        }
+
+        let days_late = (now - late_from + DAY - 1) / DAY;
+        let fee_per_day = policy.flat_fee_per_day.saturating_add(
+            self.rent_per_month
+                .saturating_mul(policy.percentage_per_day as Balance)
+                / BASIS_POINTS as Balance,
+        );
+
+        fee_per_day.saturating_mul(days_late as Balance)
+    }
+}
+
+// identifier of content stored off-chain
+#[derive(scale::Decode, scale::Encode, Debug, Clone, Eq, PartialEq)]
+#[cfg_attr(
+    feature = "std",
+    derive(scale_info::TypeInfo, ink::storage::traits::StorageLayout)
+)]
+pub enum ContentId {
+    // IPFS CID, either v0 base58btc (`Qm...`) or v1 multibase base32 (`b...`)
+    Cid(String),
+    // 32-byte hash of the content
+    Hash([u8; 32]),
+}
+
+impl ContentId {
+    pub fn is_valid(&self) -> bool {
+        match self {
+            ContentId::Cid(cid) => {
+                let bytes = cid.as_bytes();
+                if cid.starts_with("Qm") {
+                    bytes.len() == CID_V0_LENGTH
+                        && bytes.iter().all(|byte| BASE58_ALPHABET.contains(byte))
+                } else if cid.starts_with('b') {
+                    is_cid_v1(cid)
+                } else {
+                    false
+                }
+            }
+            ContentId::Hash(hash) => hash != &[0; 32],
+        }
+    }
+}
+
+// CIDv1 in multibase base32: `b` and lowercase RFC 4648 base32 without padding of
+// the version, the content codec, the multihash code, the digest length and the digest
+fn is_cid_v1(cid: &str) -> bool {
+    let encoded = match cid.as_bytes().split_first() {
+        Some((b'b', encoded)) if cid.len() <= MAX_CID_LENGTH => encoded,
+        _ => return false,
+    };
+
+    // decoded into a buffer of the longest accepted CID
+    let mut bytes = [0u8; MAX_CID_LENGTH * 5 / 8];
+    let mut length = 0;
+    let mut buffer: u16 = 0;
+    let mut bits = 0;
+    for byte in encoded {
+        let value = match byte {
+            b'a'..=b'z' => byte - b'a',
+            b'2'..=b'7' => byte - b'2' + 26,
+            _ => return false,
+        };
+        buffer = (buffer << 5) | u16::from(value);
+        bits += 5;
+        if bits >= 8 {
+            bits -= 8;
+            bytes[length] = (buffer >> bits) as u8;
+            length += 1;
+            buffer &= (1 << bits) - 1;
+        }
+    }
+
+    // bits left over by the last character are padding, a whole unused character
+    // or set padding bits mean the encoding isn't canonical
+    if bits >= 5 || buffer != 0 {
+        return false;
+    }
+
+    let mut rest = &bytes[..length];
+    let version = read_varint(&mut rest);
+    let codec = read_varint(&mut rest);
+    let hash = read_varint(&mut rest);
+    let digest_length = read_varint(&mut rest);
+
+    version == Some(1)
+        && codec.is_some()
+        && hash.is_some()
+        && !rest.is_empty()
+        && digest_length == Some(rest.len() as u64)
+}
+
+// unsigned varint of multiformats, minimally encoded in at most 9 bytes
+fn read_varint(input: &mut &[u8]) -> Option<u64> {
+    let bytes = *input;
+    let mut value: u64 = 0;
+    for (index, byte) in bytes.iter().enumerate().take(9) {
+        value |= u64::from(byte & 0x7f) << (7 * index);
+        if byte & 0x80 == 0 {
+            // a trailing zero byte would encode the same value in more bytes
+            if *byte == 0 && index > 0 {
+                return None;
+            }
+            *input = &bytes[index + 1..];
+            return Some(value);
         }
     }
+    None
+}
+
+// `type/subtype` with the token characters of RFC 6838, parameters are not allowed
+pub fn is_mime_type(value: &str) -> bool {
+    let is_token = |part: &str| {
+        !part.is_empty()
+            && part
+                .bytes()
+                .all(|byte| byte.is_ascii_alphanumeric() || b"!#$&-^_.+".contains(&byte))
+    };
+
+    value.len() <= MAX_MIME_TYPE_LENGTH
+        && match value.split_once('/') {
+            Some((kind, subtype)) => is_token(kind) && is_token(subtype),
+            None => false,
+        }
+}
+
+// photos, floor plans and description of a room stored off-chain
+#[derive(scale::Decode, scale::Encode, Debug, Clone, Eq, PartialEq)]
+#[cfg_attr(
+    feature = "std",
+    derive(scale_info::TypeInfo, ink::storage::traits::StorageLayout)
+)]
+pub struct RoomMetadata {
+    pub content: ContentId,
+    pub mime_type: String,
+    pub time_stamp: Timestamp,
+}
+
+// room with its off-chain metadata, if any was set
+#[derive(scale::Decode, scale::Encode, Debug, Clone, Eq, PartialEq)]
+#[cfg_attr(feature = "std", derive(scale_info::TypeInfo))]
+pub struct RoomListing {
+    pub room: Room,
+    pub metadata: Option<RoomMetadata>,
+}
+
+// page of rooms, `next` is the `start` of the next page or `None` after the last room
+#[derive(scale::Decode, scale::Encode, Default, Debug, Clone, Eq, PartialEq)]
+#[cfg_attr(feature = "std", derive(scale_info::TypeInfo))]
+pub struct RoomPage {
+    pub rooms: Vec<RoomListing>,
+    pub next: Option<RoomId>,
 }
 
 // pub const ROOM_AGREEMENT_STORAGE_KEY: u32 = openbrush::storage_unique_key!(RoomAgreement);
 
-#[derive(scale::Decode, Default, scale::Encode, Debug, Clone, Eq, PartialEq)]
+#[derive(scale::Decode, scale::Encode, Debug, Clone, Eq, PartialEq)]
 #[cfg_attr(
     feature = "std",
     derive(scale_info::TypeInfo, ink::storage::traits::StorageLayout)
@@ -68,8 +436,42 @@ pub struct RoomAgreement {
     pub room_address: String,
     pub rent_per_month: u128,
     pub security_deposit: u128,
-    pub lock_in_period: i32,
+    pub lock_in_period: Timestamp,
     pub time_stamp: Timestamp,
+    pub start_time: Timestamp,
+    pub end_time: Timestamp,
+    pub early_exit_penalty: Balance,
+    pub tenant: AccountId,
+    pub landlord: AccountId,
+    pub currency: Currency,
+    // billing period and late fee policy of the room at signing, rent of the agreement
+    // is due and charged on them
+    pub billing_period: Timestamp,
+    pub late_fee_policy: LateFeePolicy,
+}
+
+// room agreement default implementation
+impl Default for RoomAgreement {
+    fn default() -> Self {
+        RoomAgreement {
+            room_id: Default::default(),
+            agreement_id: Default::default(),
+            room_name: Default::default(),
+            room_address: Default::default(),
+            rent_per_month: Default::default(),
+            security_deposit: Default::default(),
+            lock_in_period: Default::default(),
+            time_stamp: Default::default(),
+            start_time: Default::default(),
+            end_time: Default::default(),
+            early_exit_penalty: Default::default(),
+            tenant: ZERO_ADDRESS.into(),
+            landlord: ZERO_ADDRESS.into(),
+            currency: Default::default(),
+            billing_period: DEFAULT_BILLING_PERIOD,
+            late_fee_policy: Default::default(),
+        }
+    }
 }
 
 // pub const ROOM_RENT_STORAGE_KEY: u32 = openbrush::storage_unique_key!(Rent);
@@ -90,6 +492,8 @@ pub struct Rent {
     pub time_stamp: Timestamp,
     pub tenant_address: AccountId,
     pub land_lord_address: AccountId,
+    pub late_fee: Balance,
+    pub currency: Currency,
 }
 
 // rent default implementation
@@ -105,18 +509,190 @@ impl Default for Rent {
             time_stamp: Default::default(),
             tenant_address: ZERO_ADDRESS.into(),
             land_lord_address: ZERO_ADDRESS.into(),
+            late_fee: Default::default(),
+            currency: Default::default(),
+        }
+    }
+}
+
+// verification status of a tenant recorded by an `ATTESTER`
+#[derive(scale::Decode, scale::Encode, Default, Debug, Clone, Copy, Eq, PartialEq)]
+#[cfg_attr(
+    feature = "std",
+    derive(scale_info::TypeInfo, ink::storage::traits::StorageLayout)
+)]
+pub enum VerificationStatus {
+    #[default]
+    Unverified,
+    Verified,
+    Revoked,
+}
+
+#[derive(scale::Decode, scale::Encode, Debug, Clone, Eq, PartialEq)]
+#[cfg_attr(
+    feature = "std",
+    derive(scale_info::TypeInfo, ink::storage::traits::StorageLayout)
+)]
+pub struct Verification {
+    pub status: VerificationStatus,
+    pub expiry: Timestamp,
+    // hash of the off-chain KYC documents
+    pub document_hash: [u8; 32],
+    pub attester: AccountId,
+    pub time_stamp: Timestamp,
+}
+
+impl Verification {
+    // tenant is verified until `expiry`
+    pub fn is_valid(&self, now: Timestamp) -> bool {
+        self.status == VerificationStatus::Verified && now < self.expiry
+    }
+}
+
+// ban requested by the landlord when terminating an agreement,
+// `expiry` of `None` bans the tenant until `unban` is called
+#[derive(scale::Decode, scale::Encode, Debug, Clone, Copy, Eq, PartialEq)]
+#[cfg_attr(feature = "std", derive(scale_info::TypeInfo))]
+pub struct BanTerms {
+    pub reason_code: u32,
+    pub expiry: Option<Timestamp>,
+}
+
+#[derive(scale::Decode, scale::Encode, Debug, Clone, Eq, PartialEq)]
+#[cfg_attr(
+    feature = "std",
+    derive(scale_info::TypeInfo, ink::storage::traits::StorageLayout)
+)]
+pub struct Ban {
+    pub reason_code: u32,
+    pub room_id: RoomId,
+    pub banned_by: AccountId,
+    pub time_stamp: Timestamp,
+    pub expiry: Option<Timestamp>,
+}
+
+impl Ban {
+    // ban without expiry stays active until it is lifted
+    pub fn is_active(&self, now: Timestamp) -> bool {
+        match self.expiry {
+            Some(expiry) => now < expiry,
+            None => true,
         }
     }
 }
 
+// rent and deposit of a room from `effective_at`, used from the first
+// billing cycle that starts after it takes effect
+#[derive(scale::Decode, scale::Encode, Debug, Clone, Eq, PartialEq)]
+#[cfg_attr(
+    feature = "std",
+    derive(scale_info::TypeInfo, ink::storage::traits::StorageLayout)
+)]
+pub struct PriceChange {
+    pub rent_per_month: Balance,
+    pub security_deposit: Balance,
+    pub effective_at: Timestamp,
+    pub scheduled_by: AccountId,
+    pub time_stamp: Timestamp,
+}
+
+// landlord action that needs the approval of the multisig signers
+#[derive(scale::Decode, scale::Encode, Debug, Clone, Eq, PartialEq)]
+#[cfg_attr(
+    feature = "std",
+    derive(scale_info::TypeInfo, ink::storage::traits::StorageLayout)
+)]
+pub enum ProposalAction {
+    // actions on an agreement only run while `agreement_id` is the current agreement
+    TerminateAgreement {
+        room_id: RoomId,
+        agreement_id: AgreementId,
+    },
+    ForfeitDeposit {
+        room_id: RoomId,
+        agreement_id: AgreementId,
+        amount: Balance,
+    },
+    ChangeRent {
+        room_id: RoomId,
+        rent_per_month: Balance,
+        security_deposit: Balance,
+    },
+}
+
+#[derive(scale::Decode, scale::Encode, Default, Debug, Clone, Copy, Eq, PartialEq)]
+#[cfg_attr(
+    feature = "std",
+    derive(scale_info::TypeInfo, ink::storage::traits::StorageLayout)
+)]
+pub enum ProposalStatus {
+    #[default]
+    Pending,
+    Executed,
+    Expired,
+}
+
+#[derive(scale::Decode, scale::Encode, Debug, Clone, Eq, PartialEq)]
+#[cfg_attr(
+    feature = "std",
+    derive(scale_info::TypeInfo, ink::storage::traits::StorageLayout)
+)]
+pub struct Proposal {
+    pub proposal_id: ProposalId,
+    pub action: ProposalAction,
+    pub proposer: AccountId,
+    pub approvals: Vec<AccountId>,
+    pub deadline: Timestamp,
+    pub status: ProposalStatus,
+    pub time_stamp: Timestamp,
+}
+
+// page of proposals, `next` is the `start` of the next page or `None` after the last proposal
+#[derive(scale::Decode, scale::Encode, Default, Debug, Clone, Eq, PartialEq)]
+#[cfg_attr(feature = "std", derive(scale_info::TypeInfo))]
+pub struct ProposalPage {
+    pub proposals: Vec<Proposal>,
+    pub next: Option<ProposalId>,
+}
+
+#[derive(scale::Decode, scale::Encode, Debug, Clone, Eq, PartialEq)]
+#[cfg_attr(feature = "std", derive(scale_info::TypeInfo))]
+pub struct RentStatus {
+    pub room_id: RoomId,
+    pub next_rent_due_date: Timestamp,
+    pub amount_due: Balance,
+    pub days_overdue: u64,
+}
+
+// native balance of the contract against what it owes, see `RoomBook::get_reconciliation`
+#[derive(scale::Decode, scale::Encode, Debug, Clone, Eq, PartialEq)]
+#[cfg_attr(feature = "std", derive(scale_info::TypeInfo))]
+pub struct Reconciliation {
+    pub contract_balance: Balance,
+    pub held_deposits: Balance,
+    pub tenant_credits: Balance,
+    pub claims: Balance,
+    pub liabilities: Balance,
+    pub surplus: Balance,
+}
+
 pub const HOTEL_STORAGE_KEY: u32 = openbrush::storage_unique_key!(Data);
 
+// layout version of the records in `Data`, bumped whenever `Room`, `RoomAgreement`
+// or `Rent` change and `migrate` learns to convert the previous layout
+pub const STORAGE_VERSION: u32 = 2;
+
+// fields of `Data` up to `storage_version` are packed in the contract root as stored by
+// version 1, the first version with `upgrade`, state added later lives in `Lazy` or
+// `Mapping` cells that read as empty until they are written
+
 #[derive(Debug)]
 #[openbrush::upgradeable_storage(HOTEL_STORAGE_KEY)]
 
 pub struct Data {
     pub tenant: AccountId,
-    pub land_lord: AccountId,
+    // account proposed by the owner to take over the contract
+    pub pending_landlord: Option<AccountId>,
     pub room_id: i32,
     pub agreement_id: i32,
     pub rent_id: i32,
@@ -125,13 +701,58 @@ pub struct Data {
     pub agreement: Mapping<AgreementId, RoomAgreement>,
     pub rent: Mapping<RentId, Rent>,
     pub room_rent_quantity: Mapping<AccountId, i32>,
+    pub claims: Mapping<AccountId, Balance>,
+    pub deposits: Mapping<AgreementId, Balance>,
+    pub total_escrow: Balance,
+    pub tenant_credit: Mapping<AccountId, Balance>,
+    pub room_agreements: Mapping<RoomId, Vec<AgreementId>>,
+    pub tenant_agreements: Mapping<AccountId, Vec<AgreementId>>,
+    pub accepted_token: Option<AccountId>,
+    // claims and held deposits paid in PSP22, keyed by `(token, account)` and `token`
+    pub token_claims: Mapping<(AccountId, AccountId), Balance>,
+    pub token_escrow: Mapping<AccountId, Balance>,
+    pub landlords: Mapping<AccountId, bool>,
+    pub verifications: Mapping<AccountId, Verification>,
+    pub bans: Mapping<AccountId, Ban>,
+    // multisig signers and number of approvals needed to execute a proposal
+    pub signers: Vec<AccountId>,
+    pub threshold: u32,
+    pub proposal_id: ProposalId,
+    pub proposals: Mapping<ProposalId, Proposal>,
+    pub notice_period: Timestamp,
+    pub price_changes: Mapping<RoomId, PriceChange>,
+    pub storage_version: u32,
+    // id the next `migrate` batch starts from
+    pub migration_next_id: Lazy<i32>,
+    // native claims and tenant credits owed by the contract
+    pub total_claims: Lazy<Balance>,
+    pub total_tenant_credit: Lazy<Balance>,
+    // storage version an ongoing `migrate` last added the claims and tenant credit of
+    // each account to the totals for
+    pub totals_counted: Mapping<AccountId, u32>,
+    // number of stored rooms and of the vacant and listed ones
+    pub room_count: Lazy<u32>,
+    pub available_room_count: Lazy<u32>,
+    // rooms of each landlord and rooms currently rented by each tenant at positions
+    // `0..count` of the account, with the position of each room kept to remove it in place
+    pub landlord_rooms: Mapping<(AccountId, u32), RoomId>,
+    pub landlord_room_index: Mapping<RoomId, u32>,
+    pub landlord_room_count: Mapping<AccountId, u32>,
+    pub tenant_rooms: Mapping<(AccountId, u32), RoomId>,
+    pub tenant_room_index: Mapping<RoomId, u32>,
+    pub tenant_room_count: Mapping<AccountId, u32>,
+    // vacant and listed rooms at positions `0..available_room_count`,
+    // with the position of each room kept to remove it in place
+    pub available_rooms: Mapping<u32, RoomId>,
+    pub available_room_index: Mapping<RoomId, u32>,
+    pub room_metadata: Mapping<RoomId, RoomMetadata>,
 }
 
 impl Default for Data {
     fn default() -> Self {
         Data {
             tenant: ZERO_ADDRESS.into(),
-            land_lord: ZERO_ADDRESS.into(),
+            pending_landlord: None,
             room_id: Default::default(),
             agreement_id: Default::default(),
             rent_id: Default::default(),
@@ -140,6 +761,40 @@ impl Default for Data {
             agreement: Mapping::default(),
             rent: Mapping::default(),
             room_rent_quantity: Mapping::default(),
+            claims: Mapping::default(),
+            deposits: Mapping::default(),
+            total_escrow: Default::default(),
+            tenant_credit: Mapping::default(),
+            room_agreements: Mapping::default(),
+            tenant_agreements: Mapping::default(),
+            accepted_token: None,
+            token_claims: Mapping::default(),
+            token_escrow: Mapping::default(),
+            landlords: Mapping::default(),
+            verifications: Mapping::default(),
+            bans: Mapping::default(),
+            signers: Vec::new(),
+            threshold: Default::default(),
+            proposal_id: Default::default(),
+            proposals: Mapping::default(),
+            notice_period: DEFAULT_NOTICE_PERIOD,
+            price_changes: Mapping::default(),
+            storage_version: Default::default(),
+            migration_next_id: Default::default(),
+            total_claims: Default::default(),
+            total_tenant_credit: Default::default(),
+            totals_counted: Mapping::default(),
+            room_count: Default::default(),
+            available_room_count: Default::default(),
+            landlord_rooms: Mapping::default(),
+            landlord_room_index: Mapping::default(),
+            landlord_room_count: Mapping::default(),
+            tenant_rooms: Mapping::default(),
+            tenant_room_index: Mapping::default(),
+            tenant_room_count: Mapping::default(),
+            available_rooms: Mapping::default(),
+            available_room_index: Mapping::default(),
+            room_metadata: Mapping::default(),
         }
     }
 }
@@ -149,18 +804,72 @@ impl Default for Data {
 pub enum HotelError {
     // Caller is not a marketplace owner.
     OwnableError(OwnableError),
-    CallerIsNotOwner,
-    CallerIsOwner,
+    AccessControlError(AccessControlError),
+    PausableError(PausableError),
+    PSP22Error(PSP22Error),
+    PSP34Error(PSP34Error),
+    ReentrancyGuardError(ReentrancyGuardError),
     NotEnoughAgreementFee,
     RoomIsNotVacant,
     NotATenantAddress,
-    NotEnoughRentFee,
     RoomNotFound,
     RoomIsVacant,
     InvalidRoomLength,
     InvalidAddressLength,
     InvalidRentPerMonth,
     InvalidSecurityDeposit,
+    InvalidWithdrawAmount,
+    InsufficientClaimBalance,
+    TransferFailed,
+    InvalidDeductionAmount,
+    InsufficientDeposit,
+    NoTenantCredit,
+    InvalidBillingPeriod,
+    RentNotDue,
+    InvalidLateFeePolicy,
+    // rent payment is short, the value is the amount still required after tenant credit
+    InsufficientRentFee(Balance),
+    InvalidLeaseTerms,
+    AgreementNotFound,
+    AgreementNotEnded,
+    TokenNotAccepted,
+    CallerIsNotRoomLandlord,
+    CallerIsRoomLandlord,
+    MissingManagerRole,
+    MissingAccountantRole,
+    MissingFrontDeskRole,
+    ContractPaused,
+    MissingAttesterRole,
+    InvalidVerificationExpiry,
+    TenantNotVerified,
+    TenantBanned,
+    TenantNotBanned,
+    InvalidBanExpiry,
+    InvalidLandlordAddress,
+    CallerIsNotPendingLandlord,
+    InvalidMultisigThreshold,
+    InvalidMultisigSigners,
+    CallerIsNotSigner,
+    InvalidProposalDeadline,
+    ProposalNotFound,
+    ProposalNotPending,
+    ProposalExpired,
+    ProposalAlreadyApproved,
+    NoticePeriodTooShort,
+    PriceChangeNotFound,
+    UpgradeFailed,
+    AlreadyMigrated,
+    InsufficientSurplus,
+    RoomNotListed,
+    InvalidRoomAttributes,
+    InvalidContentId,
+    InvalidMimeType,
+    NotAHotelRoom,
+    StaleProposal,
+    InvalidMigrationBatch,
+    MigrationPending,
+    UnsupportedStorageVersion,
+    ProposalRequired,
 }
 
 impl From<OwnableError> for HotelError {
@@ -168,3 +877,33 @@ impl From<OwnableError> for HotelError {
         HotelError::OwnableError(error)
     }
 }
+
+impl From<AccessControlError> for HotelError {
+    fn from(error: AccessControlError) -> Self {
+        HotelError::AccessControlError(error)
+    }
+}
+
+impl From<PausableError> for HotelError {
+    fn from(error: PausableError) -> Self {
+        HotelError::PausableError(error)
+    }
+}
+
+impl From<PSP22Error> for HotelError {
+    fn from(error: PSP22Error) -> Self {
+        HotelError::PSP22Error(error)
+    }
+}
+
+impl From<PSP34Error> for HotelError {
+    fn from(error: PSP34Error) -> Self {
+        HotelError::PSP34Error(error)
+    }
+}
+
+impl From<ReentrancyGuardError> for HotelError {
+    fn from(error: ReentrancyGuardError) -> Self {
+        HotelError::ReentrancyGuardError(error)
+    }
+}
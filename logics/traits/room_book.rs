@@ -1,13 +1,23 @@
 use crate::impls::room_book::{
-    room_book::Room,
-    types::{AgreementId, HotelError, RentId, RoomId, RoomResult},
+    room_book::{Room, RoomAgreement},
+    types::{
+        AgreementId, Ban, BanTerms, ContentId, Currency, HotelError, LateFeePolicy, LeaseTerms,
+        PriceChange, Proposal, ProposalAction, ProposalId, ProposalPage, Reconciliation, RentId,
+        RentStatus, RoomAttributes, RoomFilter, RoomId, RoomListing, RoomMetadata, RoomPage,
+        RoomResult, Verification, VerificationStatus,
+    },
 };
 use ink::prelude::{string::String, vec::Vec};
-use openbrush::traits::{AccountId, Timestamp};
+use openbrush::traits::{AccountId, Balance, Timestamp};
+
+#[openbrush::wrapper]
+pub type RoomBookRef = dyn RoomBook;
 
 #[openbrush::trait_definition]
 pub trait RoomBook {
-    /// Add room function where only landlord can call `add_room` function
+    /// Add room function where only registered landlord or `MANAGER` can call `add_room`
+    /// function, a registered landlord becomes the `landlord` of the room and rooms added
+    /// by the owner or a `MANAGER` belong to the hotel, whose `landlord` is the owner
     #[ink(message)]
     fn add_room(
         &mut self,
@@ -16,40 +26,535 @@ pub trait RoomBook {
         rent_per_month: u128,
         security_deposit: u128,
         time_stamp: Timestamp,
+        attributes: RoomAttributes,
     ) -> RoomResult;
 
-    /// user other than `landlord` call the `sign_agreement` function
+    /// room `landlord` or `MANAGER` changes the details of the room, a new rent or deposit
+    /// of an occupied room is scheduled after the notice period instead, new prices of
+    /// rooms of the hotel need a proposal once the multisig is set
+    #[ink(message)]
+    fn update_room(
+        &mut self,
+        room_id: RoomId,
+        room_name: String,
+        room_address: String,
+        rent_per_month: u128,
+        security_deposit: u128,
+        attributes: RoomAttributes,
+    ) -> RoomResult;
+
+    /// room `landlord` or `MANAGER` takes the room off the market, the current
+    /// agreement goes on
+    #[ink(message)]
+    fn delist_room(&mut self, room_id: RoomId) -> RoomResult;
+
+    /// room `landlord` or `MANAGER` puts the room back on the market
+    #[ink(message)]
+    fn relist_room(&mut self, room_id: RoomId) -> RoomResult;
+
+    /// room `landlord` or `MANAGER` removes a vacant room
+    #[ink(message)]
+    fn remove_room(&mut self, room_id: RoomId) -> RoomResult;
+
+    /// verified and not banned user other than room `landlord` call the `sign_agreement`
+    /// function
     #[ink(message, payable)]
     fn sign_agreement(&mut self, room_id: RoomId) -> RoomResult;
 
-    /// room musn't be vacant and user should be tenant to call `pay_rent` function
+    /// room musn't be vacant and user should be tenant to call `pay_rent` function,
+    /// late fee is charged on top of `rent_per_month` after the grace period
     #[ink(message, payable)]
     fn pay_rent(&mut self, room_id: RoomId) -> RoomResult;
 
+    /// `sign_agreement` paid in the accepted PSP22 token, the tenant must approve
+    /// `rent_per_month + security_deposit` to the contract beforehand
+    #[ink(message)]
+    fn sign_agreement_with_token(&mut self, room_id: RoomId) -> RoomResult;
+
+    /// `pay_rent` paid in the accepted PSP22 token, the tenant must approve
+    /// `rent_per_month` and any late fee to the contract beforehand
+    #[ink(message)]
+    fn pay_rent_with_token(&mut self, room_id: RoomId) -> RoomResult;
+
     /// If room is occupied by tenant and timeperiod of agreement complete then
-    /// `landlord` allowed to call this function
+    /// room `landlord` or `FRONT_DESK` allowed to call this function
     #[ink(message, payable)]
     fn agreement_completed(&mut self, room_id: RoomId) -> RoomResult;
 
-    /// On behalf of any suspecious customer, `landlord` allowed to call this function
+    /// tenant leaves the room before the agreement ends, overdue rent with its late fee
+    /// and, during the lock-in period, `early_exit_penalty` are taken from the deposit
+    #[ink(message)]
+    fn exit_agreement(&mut self, room_id: RoomId) -> RoomResult;
+
+    /// On behalf of any suspecious customer, room `landlord` or `FRONT_DESK` allowed to
+    /// call this function, passing `ban` also bans the tenant from signing new agreements,
+    /// rooms of the hotel need a proposal once the multisig is set
     #[ink(message, payable)]
-    fn agreement_terminated(&mut self, room_id: RoomId) -> RoomResult;
+    fn agreement_terminated(&mut self, room_id: RoomId, ban: Option<BanTerms>) -> RoomResult;
+
+    /// account that issued the ban or `FRONT_DESK` lifts the ban of `account`
+    #[ink(message)]
+    fn unban(&mut self, account: AccountId) -> Result<(), HotelError>;
+
+    /// get the ban of `account`, expired bans are returned as well
+    #[ink(message)]
+    fn get_ban(&self, account: AccountId) -> Option<Ban>;
 
-    /// `landlord` is allowed to call this function to get all the room
+    /// `ACCOUNTANT` is allowed to call this function to get up to `MAX_PAGE_SIZE` rooms,
+    /// `get_rooms_page` pages through all of them with their metadata
     #[ink(message)]
     fn get_room(&mut self) -> Result<Vec<Room>, HotelError>;
 
-    /// `customer` can view all available rooms
+    /// `customer` can view up to `MAX_PAGE_SIZE` vacant and listed rooms in no particular
+    /// order, `get_available_rooms_page` pages through all of them by room id, rooms come
+    /// without their metadata so existing callers keep decoding `Vec<Room>`, use
+    /// `get_available_rooms_page` or `get_room_metadata` for it
     #[ink(message)]
     fn get_available_room(&self) -> Vec<Room>;
 
-    /// `landlord` of the contract
+    /// landlord of the room or `MANAGER` points the room at its off-chain media,
+    /// `content` is an IPFS CID or a 32-byte hash of a `mime_type` document
+    #[ink(message)]
+    fn set_room_metadata(
+        &mut self,
+        room_id: RoomId,
+        content: ContentId,
+        mime_type: String,
+    ) -> RoomResult;
+
+    /// get the off-chain metadata of `room_id`
+    #[ink(message)]
+    fn get_room_metadata(&self, room_id: RoomId) -> Option<RoomMetadata>;
+
+    /// get up to `limit` rooms of `landlord` from position `start`, at most `MAX_PAGE_SIZE`,
+    /// the owner of the contract gets their own rooms followed by the rooms of the hotel
+    #[ink(message)]
+    fn get_rooms_by_landlord(
+        &self,
+        landlord: AccountId,
+        start: u32,
+        limit: u32,
+    ) -> Vec<RoomListing>;
+
+    /// get up to `limit` rooms currently rented by `tenant` from position `start`,
+    /// at most `MAX_PAGE_SIZE`
+    #[ink(message)]
+    fn get_rooms_by_tenant(&self, tenant: AccountId, start: u32, limit: u32) -> Vec<RoomListing>;
+
+    /// get the number of rooms of `landlord`, rooms of the hotel count for the owner
+    #[ink(message)]
+    fn landlord_room_count(&self, landlord: AccountId) -> u32;
+
+    /// get the number of rooms currently rented by `tenant`
+    #[ink(message)]
+    fn tenant_room_count(&self, tenant: AccountId) -> u32;
+
+    /// `ACCOUNTANT` is allowed to page through all the rooms from `start`,
+    /// at most `limit` rooms are returned
+    #[ink(message)]
+    fn get_rooms_page(&mut self, start: RoomId, limit: u32) -> Result<RoomPage, HotelError>;
+
+    /// `customer` can page through the vacant and listed rooms from `start`,
+    /// a page can hold fewer than `limit` rooms before the last one
+    #[ink(message)]
+    fn get_available_rooms_page(&self, start: RoomId, limit: u32) -> RoomPage;
+
+    /// get the number of rooms
+    #[ink(message)]
+    fn room_count(&self) -> u32;
+
+    /// get the number of vacant and listed rooms
+    #[ink(message)]
+    fn available_room_count(&self) -> u32;
+
+    /// `customer` can page through the vacant and listed rooms matching `filter` from `start`,
+    /// a page can hold fewer than `limit` rooms before the last one
+    #[ink(message)]
+    fn search_rooms(&self, filter: RoomFilter, start: RoomId, limit: u32) -> RoomPage;
+
+    /// owner of the contract stops new agreements and rent payments,
+    /// refunds, withdrawals and queries stay available
+    #[ink(message)]
+    fn pause(&mut self) -> Result<(), HotelError>;
+
+    /// owner of the contract resumes new agreements and rent payments
+    #[ink(message)]
+    fn unpause(&mut self) -> Result<(), HotelError>;
+
+    /// `landlord` of the contract, the operator who registers the other landlords,
+    /// always the owner of the contract
     #[ink(message)]
     fn get_landlord(&self) -> AccountId;
 
+    /// owner of the contract proposes `new_landlord` to take over the contract,
+    /// nothing changes until `new_landlord` calls `accept_landlordship`
+    #[ink(message)]
+    fn propose_new_landlord(&mut self, new_landlord: AccountId) -> Result<(), HotelError>;
+
+    /// proposed landlord becomes the owner of the contract and the admin of the staff roles
+    #[ink(message)]
+    fn accept_landlordship(&mut self) -> Result<(), HotelError>;
+
+    /// get the landlord proposed by the owner of the contract
+    #[ink(message)]
+    fn get_pending_landlord(&self) -> Option<AccountId>;
+
+    /// owner of the contract approves `landlord` to add rooms
+    #[ink(message)]
+    fn register_landlord(&mut self, landlord: AccountId) -> Result<(), HotelError>;
+
+    /// owner of the contract stops `landlord` from adding new rooms
+    #[ink(message)]
+    fn remove_landlord(&mut self, landlord: AccountId) -> Result<(), HotelError>;
+
+    /// check whether `account` is a registered landlord
+    #[ink(message)]
+    fn is_landlord(&self, account: AccountId) -> bool;
+
     #[ink(message)]
     fn get_room_rent_count(&self, user: AccountId) -> Option<i32>;
 
+    /// withdraw `amount` from the caller's claimable balance
+    #[ink(message)]
+    fn withdraw(&mut self, amount: Balance) -> Result<(), HotelError>;
+
+    /// get the claimable balance of `account`
+    #[ink(message)]
+    fn balance_of_claims(&self, account: AccountId) -> Balance;
+
+    /// withdraw `amount` of `token` from the caller's claimable balance
+    #[ink(message)]
+    fn withdraw_token(&mut self, token: AccountId, amount: Balance) -> Result<(), HotelError>;
+
+    /// get the claimable balance of `account` in `token`
+    #[ink(message)]
+    fn balance_of_token_claims(&self, token: AccountId, account: AccountId) -> Balance;
+
+    /// owner of the contract sets the PSP22 token accepted for rent and deposits
+    #[ink(message)]
+    fn set_accepted_token(&mut self, token: Option<AccountId>) -> Result<(), HotelError>;
+
+    /// get the PSP22 token accepted for rent and deposits
+    #[ink(message)]
+    fn get_accepted_token(&self) -> Option<AccountId>;
+
+    /// `landlord` sets the billing period of the room used for its next agreements
+    #[ink(message)]
+    fn set_billing_period(&mut self, room_id: RoomId, billing_period: Timestamp) -> RoomResult;
+
+    /// `landlord` sets the late fee charged per day after the grace period of the due date
+    /// for the next agreements of the room
+    #[ink(message)]
+    fn set_late_fee_policy(&mut self, room_id: RoomId, policy: LateFeePolicy) -> RoomResult;
+
+    /// `landlord` sets the lease duration, lock-in period and early exit penalty
+    /// used for the next agreements of the room
+    #[ink(message)]
+    fn set_lease_terms(&mut self, room_id: RoomId, terms: LeaseTerms) -> RoomResult;
+
+    /// get the terms of `agreement_id`
+    #[ink(message)]
+    fn get_agreement(&self, agreement_id: AgreementId) -> Option<RoomAgreement>;
+
+    /// get all agreements signed for `room_id`, oldest first
+    #[ink(message)]
+    fn get_agreements_for_room(&self, room_id: RoomId) -> Vec<RoomAgreement>;
+
+    /// get all agreements signed by `tenant`, oldest first
+    #[ink(message)]
+    fn get_agreements_for_tenant(&self, tenant: AccountId) -> Vec<RoomAgreement>;
+
+    /// get the next due date, amount due and days overdue of an occupied room
+    #[ink(message)]
+    fn get_rent_status(&self, room_id: RoomId) -> Result<RentStatus, HotelError>;
+
+    /// `landlord` deducts `amount` from the held security deposit of an occupied room,
+    /// the deducted amount is credited to the room `landlord`, rooms of the hotel need a
+    /// proposal once the multisig is set
+    #[ink(message)]
+    fn deduct_deposit(&mut self, room_id: RoomId, amount: Balance) -> RoomResult;
+
+    /// get the security deposit held for the current agreement of the room
+    #[ink(message)]
+    fn get_room_deposit(&self, room_id: RoomId) -> Balance;
+
+    /// get the security deposit held for `agreement_id`
+    #[ink(message)]
+    fn get_agreement_deposit(&self, agreement_id: AgreementId) -> Balance;
+
+    /// get the total security deposit held by the contract
+    #[ink(message)]
+    fn get_total_escrow(&self) -> Balance;
+
+    /// get the total security deposit held by the contract in `token`
+    #[ink(message)]
+    fn get_token_escrow(&self, token: AccountId) -> Balance;
+
+    /// get the overpaid amount of `account` that will be applied to the next `pay_rent`
+    #[ink(message)]
+    fn get_tenant_credit(&self, account: AccountId) -> Balance;
+
+    /// move the caller's unused tenant credit to their claimable balance
+    #[ink(message)]
+    fn claim_tenant_credit(&mut self) -> Result<Balance, HotelError>;
+
+    /// `ATTESTER` records the verification `status` of `account` valid until `expiry`,
+    /// `document_hash` is the hash of the off-chain KYC documents
+    #[ink(message)]
+    fn attest_tenant(
+        &mut self,
+        account: AccountId,
+        status: VerificationStatus,
+        expiry: Timestamp,
+        document_hash: [u8; 32],
+    ) -> Result<(), HotelError>;
+
+    /// get the verification record of `account`
+    #[ink(message)]
+    fn get_verification(&self, account: AccountId) -> Option<Verification>;
+
+    /// check whether `account` is verified at the current block timestamp
+    #[ink(message)]
+    fn is_verified(&self, account: AccountId) -> bool;
+
+    /// room `landlord` or `MANAGER` schedules new `rent_per_month` and `security_deposit`
+    /// from `effective_at`, which must be at least the notice period away, it replaces
+    /// any pending change of the room, rooms of the hotel need a proposal once the
+    /// multisig is set
+    #[ink(message)]
+    fn schedule_price_change(
+        &mut self,
+        room_id: RoomId,
+        rent_per_month: Balance,
+        security_deposit: Balance,
+        effective_at: Timestamp,
+    ) -> RoomResult;
+
+    /// room `landlord` or `MANAGER` cancels the pending price change of the room
+    #[ink(message)]
+    fn cancel_price_change(&mut self, room_id: RoomId) -> RoomResult;
+
+    /// get the pending price change of `room_id`
+    #[ink(message)]
+    fn get_price_change(&self, room_id: RoomId) -> Option<PriceChange>;
+
+    /// owner of the contract sets the minimum notice of a price change, never below the default
+    #[ink(message)]
+    fn set_notice_period(&mut self, notice_period: Timestamp) -> Result<(), HotelError>;
+
+    /// get the minimum notice of a price change
+    #[ink(message)]
+    fn get_notice_period(&self) -> Timestamp;
+
+    /// get the native balance of the contract against the held deposits,
+    /// tenant credits and claims it owes
+    #[ink(message)]
+    fn get_reconciliation(&self) -> Reconciliation;
+
+    /// owner of the contract withdraws `amount` of the balance above what the contract owes
+    #[ink(message)]
+    fn withdraw_surplus(&mut self, amount: Balance) -> Result<(), HotelError>;
+
+    /// owner of the contract replaces the code of the contract with `code_hash`,
+    /// `migrate` must be called right after when the new code changes the storage version
+    #[ink(message)]
+    fn upgrade(&mut self, code_hash: [u8; 32]) -> Result<(), HotelError>;
+
+    /// owner of the contract converts the rooms, agreements and rents with ids from `start`
+    /// to `start + limit` stored by the previous storage version to the current layout,
+    /// returns the `start` of the next batch or `None` once the storage version is bumped,
+    /// upgrades start at storage version 1
+    #[ink(message)]
+    fn migrate(&mut self, start: i32, limit: u32) -> Result<Option<i32>, HotelError>;
+
+    /// get the storage version of the contract data
+    #[ink(message)]
+    fn get_storage_version(&self) -> u32;
+
+    /// owner of the contract sets the multisig `signers` and the number of approvals
+    /// needed to execute a proposal
+    #[ink(message)]
+    fn set_multisig(&mut self, signers: Vec<AccountId>, threshold: u32) -> Result<(), HotelError>;
+
+    /// get the multisig signers
+    #[ink(message)]
+    fn get_signers(&self) -> Vec<AccountId>;
+
+    /// get the number of approvals needed to execute a proposal
+    #[ink(message)]
+    fn get_threshold(&self) -> u32;
+
+    /// signer proposes `action`, which can be approved until `deadline`,
+    /// the proposal counts as approved by the proposer
+    #[ink(message)]
+    fn create_proposal(
+        &mut self,
+        action: ProposalAction,
+        deadline: Timestamp,
+    ) -> Result<ProposalId, HotelError>;
+
+    /// signer approves `proposal_id`, the action is executed once the threshold is met
+    #[ink(message)]
+    fn approve_proposal(&mut self, proposal_id: ProposalId) -> Result<(), HotelError>;
+
+    /// get the proposal of `proposal_id`
+    #[ink(message)]
+    fn get_proposal(&self, proposal_id: ProposalId) -> Option<Proposal>;
+
+    /// get up to `limit` proposals from `start`, oldest first, at most `MAX_PAGE_SIZE`
+    #[ink(message)]
+    fn get_proposals(&self, start: ProposalId, limit: u32) -> ProposalPage;
+
+    /// terminate the agreement of `room_id` and ban the tenant with `ban`
+    fn terminate_agreement(&mut self, room_id: RoomId, ban: Option<BanTerms>) -> RoomResult;
+
+    /// credit `amount` of the deposit held for `room_id` to the room `landlord`
+    fn forfeit_deposit(&mut self, room_id: RoomId, amount: Balance) -> RoomResult;
+
+    /// schedule `rent_per_month` and `security_deposit` of `room_id` after the notice period
+    fn change_rent(
+        &mut self,
+        room_id: RoomId,
+        rent_per_month: Balance,
+        security_deposit: Balance,
+    ) -> RoomResult;
+
+    /// validate and store the pending price change of `room_id`
+    fn schedule_change(
+        &mut self,
+        room_id: RoomId,
+        rent_per_month: Balance,
+        security_deposit: Balance,
+        effective_at: Timestamp,
+    ) -> RoomResult;
+
+    /// get the pending price change of `room` if it takes effect by the start of
+    /// its next billing cycle
+    fn pending_change_due(&self, room: &Room) -> Option<PriceChange>;
+
+    /// get the room with its pending price change applied once it takes effect
+    fn apply_price_change(&mut self, room_id: RoomId) -> Result<Room, HotelError>;
+
+    /// record the caller's approval of `proposal_id` and execute it once the threshold is met
+    fn add_approval(&mut self, proposal_id: ProposalId) -> Result<(), HotelError>;
+
+    /// check `action` targets a room of the hotel and, for actions on an agreement, that it
+    /// is still the current agreement of the room
+    fn check_proposal_action(&self, action: &ProposalAction) -> Result<(), HotelError>;
+
+    /// execute the action of an approved proposal
+    fn execute_proposal(&mut self, action: ProposalAction) -> Result<(), HotelError>;
+
+    /// check the details shared by `add_room` and `update_room`
+    fn validate_room(
+        &self,
+        room_name: &str,
+        room_address: &str,
+        rent_per_month: u128,
+        security_deposit: u128,
+        attributes: &RoomAttributes,
+    ) -> Result<(), HotelError>;
+
+    /// collect up to `limit` rooms from `start`, with a `filter` only the vacant and listed
+    /// rooms matching it
+    fn rooms_page(&self, start: RoomId, limit: u32, filter: Option<&RoomFilter>) -> RoomPage;
+
+    /// pair `room` with its metadata for the listing queries
+    fn room_listing(&self, room: Room) -> RoomListing;
+
+    /// account `room` belongs to, rooms of the hotel belong to the owner of the contract
+    fn room_landlord(&self, room: &Room) -> AccountId;
+
+    /// `room` with the account it belongs to as its `landlord`
+    fn resolve_landlord(&self, room: Room) -> Room;
+
+    /// billing period of the current agreement of `room`
+    fn agreement_billing_period(&self, room: &Room) -> Timestamp;
+
+    /// late fee policy of the current agreement of `room`
+    fn agreement_late_fee_policy(&self, room: &Room) -> LateFeePolicy;
+
+    /// keep the vacancy index in line with `room_id` changing availability
+    fn track_availability(&mut self, room_id: RoomId, was_available: bool, is_available: bool);
+
+    /// add `room_id` to or remove it from the rooms of `landlord`
+    fn index_landlord_room(&mut self, landlord: AccountId, room_id: RoomId, add: bool);
+
+    /// add `room_id` to or remove it from the rooms rented by `tenant`
+    fn index_tenant_room(&mut self, tenant: AccountId, room_id: RoomId, add: bool);
+
+    /// record the agreement for `room_id` paid with `value` in `currency`
+    fn book_room(&mut self, room_id: RoomId, value: Balance, currency: Currency) -> RoomResult;
+
+    /// record the rent for `room_id` paid with `value` in `currency`
+    fn collect_rent(&mut self, room_id: RoomId, value: Balance, currency: Currency) -> RoomResult;
+
+    /// mint the PSP34 lease token of `agreement` to its tenant
+    fn mint_lease(&mut self, agreement: &RoomAgreement) -> Result<(), HotelError>;
+
+    /// burn the PSP34 lease token of `agreement_id` from its current owner
+    fn burn_lease(&mut self, agreement_id: AgreementId) -> Result<(), HotelError>;
+
+    /// pull `amount` of `token` from `from` to the contract
+    fn pull_token(
+        &mut self,
+        token: AccountId,
+        from: AccountId,
+        amount: Balance,
+    ) -> Result<(), HotelError>;
+
+    /// get the native claims owed by the contract
+    fn total_claims(&self) -> Balance;
+
+    /// get the tenant credits owed by the contract
+    fn total_tenant_credit(&self) -> Balance;
+
+    /// whether changes of the claims and tenant credit of `account` are added to the totals
+    fn tracks_totals(&self, account: AccountId) -> bool;
+
+    /// update the native claims owed by the contract after a claim of `account` changed
+    fn track_claim(&mut self, account: AccountId, previous: Balance, current: Balance);
+
+    /// update the tenant credits owed by the contract after the credit of `account` changed
+    fn track_tenant_credit(&mut self, account: AccountId, previous: Balance, current: Balance);
+
+    /// add the claims and tenant credit of `account` to the totals once during `migrate`
+    fn count_totals(&mut self, account: AccountId);
+
+    /// get room `room_id` converted to the current layout if it is still stored with the
+    /// layout of `from_version`
+    fn old_room(&self, room_id: RoomId, from_version: u32) -> Option<Room>;
+
+    /// convert room `room_id` stored by `from_version` and add it to the counts and indexes
+    fn migrate_room(&mut self, room_id: RoomId, from_version: u32);
+
+    /// convert agreement `agreement_id` stored by `from_version` and count its parties
+    fn migrate_agreement(&mut self, agreement_id: AgreementId, from_version: u32);
+
+    /// convert agreement `agreement_id` if it is still stored with the layout of version 1
+    fn convert_agreement(&mut self, agreement_id: AgreementId);
+
+    /// count the parties of rent `rent_id`, its layout is unchanged since version 1
+    fn migrate_rent(&mut self, rent_id: RentId);
+
+    /// convert proposal `proposal_id` if it is still stored with the layout of version 1
+    fn migrate_proposal(&mut self, proposal_id: ProposalId, from_version: u32);
+
+    /// get the accepted PSP22 token or fail if none is configured
+    fn accepted_token(&self) -> Result<AccountId, HotelError>;
+
+    /// credit `amount` in `currency` to the claimable balance of `account`
+    fn credit_claim(&mut self, account: AccountId, amount: Balance, currency: Currency);
+
+    /// add `amount` to the tenant credit of `account`
+    fn add_tenant_credit(&mut self, account: AccountId, amount: Balance);
+
+    /// hold `amount` in `currency` in escrow for `agreement_id`
+    fn hold_deposit(&mut self, agreement_id: AgreementId, amount: Balance, currency: Currency);
+
+    /// release `amount` of the deposit held for `agreement_id` to the claims of `account`
+    fn release_deposit(&mut self, agreement_id: AgreementId, amount: Balance, account: AccountId);
+
     /// get the `next_room_id`
     fn next_room_id(&mut self) -> RoomId;
 
@@ -58,4 +563,7 @@ pub trait RoomBook {
 
     /// geht the `next_rent_id`
     fn next_rent_id(&mut self) -> RentId;
+
+    /// get the `next_proposal_id`
+    fn next_proposal_id(&mut self) -> ProposalId;
 }
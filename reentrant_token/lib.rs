@@ -0,0 +1,93 @@
+#![cfg_attr(not(feature = "std"), no_std)]
+
+// PSP22 look-alike used by the e2e tests of the hotel contract, it is a landlord of the
+// hotel and its `transfer_from` withdraws its claims before the hotel call that pulls
+// the tokens has finished
+#[ink::contract]
+mod reentrant_token {
+    use ink::{
+        env::{
+            call::{build_call, ExecutionInput, Selector},
+            DefaultEnvironment,
+        },
+        prelude::{string::String, vec::Vec},
+    };
+    use logics::impls::room_book::types::{HotelError, RoomAttributes, RoomId};
+    use openbrush::contracts::psp22::PSP22Error;
+    use scale::{Decode, Encode};
+
+    #[ink(storage)]
+    pub struct ReentrantToken {
+        hotel: AccountId,
+        // claim withdrawn from the hotel by `transfer_from`
+        amount: Balance,
+        // encoded result of the nested `withdraw`
+        reentry_result: Vec<u8>,
+    }
+
+    impl ReentrantToken {
+        #[ink(constructor)]
+        pub fn new(hotel: AccountId, amount: Balance) -> Self {
+            Self {
+                hotel,
+                amount,
+                reentry_result: Vec::new(),
+            }
+        }
+
+        // adds a room to the hotel with this contract as its landlord
+        #[ink(message)]
+        pub fn add_room(&mut self, rent_per_month: Balance, security_deposit: Balance) -> RoomId {
+            build_call::<DefaultEnvironment>()
+                .call(self.hotel)
+                .gas_limit(0)
+                .exec_input(
+                    ExecutionInput::new(Selector::new(ink::selector_bytes!("RoomBook::add_room")))
+                        .push_arg(String::from("token room"))
+                        .push_arg(String::from("token address"))
+                        .push_arg(rent_per_month)
+                        .push_arg(security_deposit)
+                        .push_arg(0 as Timestamp)
+                        .push_arg(RoomAttributes::default()),
+                )
+                .returns::<Result<RoomId, HotelError>>()
+                .invoke()
+                .expect("add_room failed")
+        }
+
+        // same selector as `PSP22::transfer_from`, withdraws `amount` of the claims of
+        // this contract, which would succeed outside of the hotel call
+        #[ink(message, selector = 0x54b3c76e)]
+        pub fn transfer_from(
+            &mut self,
+            _from: AccountId,
+            _to: AccountId,
+            _value: Balance,
+            _data: Vec<u8>,
+        ) -> Result<(), PSP22Error> {
+            let result = build_call::<DefaultEnvironment>()
+                .call(self.hotel)
+                .gas_limit(0)
+                .exec_input(
+                    ExecutionInput::new(Selector::new(ink::selector_bytes!("RoomBook::withdraw")))
+                        .push_arg(self.amount),
+                )
+                .returns::<Result<(), HotelError>>()
+                .try_invoke();
+
+            let reentry_result = match result {
+                Ok(Ok(value)) => Some(value),
+                _ => None,
+            };
+            self.reentry_result = reentry_result.encode();
+
+            Ok(())
+        }
+
+        // result of the nested `withdraw`, `None` if the runtime denied the call
+        #[ink(message)]
+        pub fn reentry_result(&self) -> Option<Result<(), HotelError>> {
+            Decode::decode(&mut &self.reentry_result[..]).unwrap_or(None)
+        }
+    }
+}